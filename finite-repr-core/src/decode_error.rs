@@ -0,0 +1,36 @@
+use std::fmt;
+
+/**
+ * Why a number doesn't decode, returned by
+ * `FiniteDecoding::try_from_finite`.
+ */
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum DecodeError {
+  /**
+   * The number is negative, or at or beyond `REPRS`.
+   */
+  OutOfRange,
+  /**
+   * The number is reserved by `#[finite(reserve = N)]` for
+   * values that haven't been added to the type yet.
+   */
+  Reserved,
+  /**
+   * The number is in range but isn't a representation, such
+   * as one between the discriminants of an enum.
+   */
+  Invalid,
+}
+
+impl fmt::Display for DecodeError {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    let message = match self {
+      DecodeError::OutOfRange => "the number is out of range",
+      DecodeError::Reserved => "the number is reserved",
+      DecodeError::Invalid => "the number isn't a representation",
+    };
+    f.write_str(message)
+  }
+}
+
+impl std::error::Error for DecodeError {}
@@ -0,0 +1,142 @@
+use crate::finite_value::FiniteValue;
+use crate::repr::FiniteRepr;
+use crate::traits::{FiniteDecoding, FiniteEncoding};
+
+/**
+ * A type whose encodings fit in `Compact`, the narrowest
+ * unsigned integer with at least `BYTES_REQUIRED` bytes.
+ * Derive this with `#[derive(FiniteCompact)]`.
+ */
+pub trait FiniteCompact: FiniteRepr {
+  type Compact: FiniteValue;
+
+  /**
+   * Encodes into the narrowest integer that holds every
+   * representation of this type.
+   */
+  #[allow(clippy::wrong_self_convention)]
+  fn into_compact(&self) -> Option<Self::Compact>
+  where
+    Self: FiniteEncoding,
+  {
+    self.into_finite::<Self::Compact>()
+  }
+
+  /**
+   * The inverse of `FiniteCompact::into_compact`.
+   */
+  fn from_compact(compact: Self::Compact) -> Option<Self>
+  where
+    Self: FiniteDecoding,
+  {
+    Self::from_finite(compact)
+  }
+}
+
+/**
+ * The narrowest integer the encodings of `T` fit in.
+ */
+pub type Smallest<T> = <T as FiniteCompact>::Compact;
+
+/**
+ * A number of bytes, mapped to the narrowest unsigned integer
+ * with at least that many by `CompactWidth`.
+ */
+pub struct Width<const BYTES: usize>;
+
+pub trait CompactWidth {
+  type Int: FiniteValue;
+}
+
+impl CompactWidth for Width<0> {
+  type Int = u8;
+}
+
+impl CompactWidth for Width<1> {
+  type Int = u8;
+}
+
+impl CompactWidth for Width<2> {
+  type Int = u16;
+}
+
+impl CompactWidth for Width<3> {
+  type Int = u32;
+}
+
+impl CompactWidth for Width<4> {
+  type Int = u32;
+}
+
+impl CompactWidth for Width<5> {
+  type Int = u64;
+}
+
+impl CompactWidth for Width<6> {
+  type Int = u64;
+}
+
+impl CompactWidth for Width<7> {
+  type Int = u64;
+}
+
+impl CompactWidth for Width<8> {
+  type Int = u64;
+}
+
+impl CompactWidth for Width<9> {
+  type Int = u128;
+}
+
+impl CompactWidth for Width<10> {
+  type Int = u128;
+}
+
+impl CompactWidth for Width<11> {
+  type Int = u128;
+}
+
+impl CompactWidth for Width<12> {
+  type Int = u128;
+}
+
+impl CompactWidth for Width<13> {
+  type Int = u128;
+}
+
+impl CompactWidth for Width<14> {
+  type Int = u128;
+}
+
+impl CompactWidth for Width<15> {
+  type Int = u128;
+}
+
+impl CompactWidth for Width<16> {
+  type Int = u128;
+}
+
+impl FiniteCompact for bool {
+  type Compact = u8;
+}
+
+impl FiniteCompact for u8 {
+  type Compact = u8;
+}
+
+impl FiniteCompact for u16 {
+  type Compact = u16;
+}
+
+#[cfg(target_pointer_width = "64")]
+impl FiniteCompact for u32 {
+  type Compact = u32;
+}
+
+impl FiniteCompact for i8 {
+  type Compact = u8;
+}
+
+impl FiniteCompact for i16 {
+  type Compact = u16;
+}
@@ -0,0 +1,281 @@
+use crate::finite_value::FiniteValue;
+use std::num::{NonZeroU128, NonZeroU16, NonZeroU32, NonZeroU64, NonZeroU8, NonZeroUsize};
+
+/**
+ * A type that values can be encoded into by way of a
+ * `FiniteValue`, for types that can't do arithmetic themselves.
+ * Every `FiniteValue` is a target of itself.
+ */
+pub trait FiniteTarget: Sized {
+  /**
+   * The number this target is converted from & into.
+   */
+  type Value: FiniteValue;
+
+  /**
+   * The most representations a type can have to be encoded
+   * into this target, larger types fail to compile.
+   */
+  const MAX_REPRS: usize = usize::MAX;
+
+  /**
+   * The most representations this target can hold, used by
+   * `#[finite(fits = "...")]` to check a type at compile time.
+   */
+  const CAPACITY: usize = {
+    let capacity = Self::Value::MAX_USIZE.saturating_add(1);
+    if capacity < Self::MAX_REPRS {
+      capacity
+    } else {
+      Self::MAX_REPRS
+    }
+  };
+
+  /**
+   * Fails when the number has no equivalent in this target.
+   */
+  fn from_value(value: Self::Value) -> Option<Self>;
+
+  /**
+   * Fails when this target is outside the range of the number.
+   */
+  fn into_value(self) -> Option<Self::Value>;
+}
+
+impl<T: FiniteValue> FiniteTarget for T {
+  type Value = T;
+
+  fn from_value(value: Self::Value) -> Option<Self> {
+    Some(value)
+  }
+
+  fn into_value(self) -> Option<Self::Value> {
+    Some(self)
+  }
+}
+
+// Each non-zero integer is its encoding plus one, so the
+// first value is encoded as one & none of them are zero.
+impl FiniteTarget for NonZeroU8 {
+  type Value = u8;
+
+  const CAPACITY: usize = <u8 as FiniteValue>::MAX_USIZE;
+
+  fn from_value(value: Self::Value) -> Option<Self> {
+    NonZeroU8::new(value.checked_add(1)?)
+  }
+
+  fn into_value(self) -> Option<Self::Value> {
+    Some(self.get() - 1)
+  }
+}
+
+impl FiniteTarget for NonZeroU16 {
+  type Value = u16;
+
+  const CAPACITY: usize = <u16 as FiniteValue>::MAX_USIZE;
+
+  fn from_value(value: Self::Value) -> Option<Self> {
+    NonZeroU16::new(value.checked_add(1)?)
+  }
+
+  fn into_value(self) -> Option<Self::Value> {
+    Some(self.get() - 1)
+  }
+}
+
+impl FiniteTarget for NonZeroU32 {
+  type Value = u32;
+
+  const CAPACITY: usize = <u32 as FiniteValue>::MAX_USIZE;
+
+  fn from_value(value: Self::Value) -> Option<Self> {
+    NonZeroU32::new(value.checked_add(1)?)
+  }
+
+  fn into_value(self) -> Option<Self::Value> {
+    Some(self.get() - 1)
+  }
+}
+
+impl FiniteTarget for NonZeroU64 {
+  type Value = u64;
+
+  const CAPACITY: usize = <u64 as FiniteValue>::MAX_USIZE;
+
+  fn from_value(value: Self::Value) -> Option<Self> {
+    NonZeroU64::new(value.checked_add(1)?)
+  }
+
+  fn into_value(self) -> Option<Self::Value> {
+    Some(self.get() - 1)
+  }
+}
+
+impl FiniteTarget for NonZeroU128 {
+  type Value = u128;
+
+  const CAPACITY: usize = <u128 as FiniteValue>::MAX_USIZE;
+
+  fn from_value(value: Self::Value) -> Option<Self> {
+    NonZeroU128::new(value.checked_add(1)?)
+  }
+
+  fn into_value(self) -> Option<Self::Value> {
+    Some(self.get() - 1)
+  }
+}
+
+impl FiniteTarget for NonZeroUsize {
+  type Value = usize;
+
+  const CAPACITY: usize = <usize as FiniteValue>::MAX_USIZE;
+
+  fn from_value(value: Self::Value) -> Option<Self> {
+    NonZeroUsize::new(value.checked_add(1)?)
+  }
+
+  fn into_value(self) -> Option<Self::Value> {
+    Some(self.get() - 1)
+  }
+}
+
+/**
+ * A number stored as `N` bytes with the most significant first,
+ * use `bytes_required` to find the fewest bytes a type needs.
+ * Comparing the bytes orders them the same as the number.
+ */
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub struct BigEndian<const N: usize>(pub [u8; N]);
+
+/**
+ * A number stored as `N` bytes with the least significant first,
+ * use `bytes_required` to find the fewest bytes a type needs.
+ */
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct LittleEndian<const N: usize>(pub [u8; N]);
+
+impl<const N: usize> FiniteTarget for BigEndian<N> {
+  type Value = usize;
+
+  const CAPACITY: usize = byte_capacity(N);
+
+  fn from_value(value: Self::Value) -> Option<Self> {
+    let mut bytes = [0; N];
+    let rest = write_bytes(value, bytes.iter_mut().rev());
+    Some(BigEndian(bytes)).filter(|_| rest == 0)
+  }
+
+  fn into_value(self) -> Option<Self::Value> {
+    read_bytes(self.0.iter())
+  }
+}
+
+impl<const N: usize> FiniteTarget for LittleEndian<N> {
+  type Value = usize;
+
+  const CAPACITY: usize = byte_capacity(N);
+
+  fn from_value(value: Self::Value) -> Option<Self> {
+    let mut bytes = [0; N];
+    let rest = write_bytes(value, bytes.iter_mut());
+    Some(LittleEndian(bytes)).filter(|_| rest == 0)
+  }
+
+  fn into_value(self) -> Option<Self::Value> {
+    read_bytes(self.0.iter().rev())
+  }
+}
+
+/**
+ * The number of values `bytes` bytes can hold, saturated to
+ * a `usize`.
+ */
+const fn byte_capacity(bytes: usize) -> usize {
+  if bytes >= std::mem::size_of::<usize>() {
+    usize::MAX
+  } else {
+    1 << (8 * bytes)
+  }
+}
+
+/**
+ * Writes `value` to `bytes` from the least significant byte,
+ * returning whatever didn't fit.
+ */
+fn write_bytes<'a>(value: usize, bytes: impl Iterator<Item = &'a mut u8>) -> usize {
+  let mut rest = value;
+  for byte in bytes {
+    *byte = rest as u8;
+    rest = rest.checked_shr(8).unwrap_or(0);
+  }
+  rest
+}
+
+/**
+ * Reads `bytes` from the most significant byte, failing if
+ * they're too large for a `usize`.
+ */
+fn read_bytes<'a>(bytes: impl Iterator<Item = &'a u8>) -> Option<usize> {
+  let mut value: usize = 0;
+  for byte in bytes {
+    value = value.checked_mul(256)?.checked_add(*byte as usize)?;
+  }
+  Some(value)
+}
+
+/**
+ * An integer stored as an `f64`, for numbers passed through
+ * JavaScript or JSON. Only integers up to `MAX` are exact in an
+ * `f64`, so types with more representations than that fail to
+ * compile when encoded into one.
+ */
+#[derive(Clone, Copy, PartialEq, PartialOrd, Debug)]
+pub struct JsSafeInt(pub f64);
+
+impl JsSafeInt {
+  /**
+   * The largest integer that every smaller integer can also be
+   * exactly represented below, `Number.MAX_SAFE_INTEGER`.
+   */
+  pub const MAX: u64 = (1 << 53) - 1;
+}
+
+impl FiniteTarget for JsSafeInt {
+  type Value = u64;
+
+  const MAX_REPRS: usize = saturate_usize(JsSafeInt::MAX + 1);
+
+  fn from_value(value: Self::Value) -> Option<Self> {
+    Some(JsSafeInt(value as f64)).filter(|_| value <= JsSafeInt::MAX)
+  }
+
+  fn into_value(self) -> Option<Self::Value> {
+    let JsSafeInt(number) = self;
+    let exact = number >= 0.0 && number <= JsSafeInt::MAX as f64 && number.fract() == 0.0;
+    Some(number as u64).filter(|_| exact)
+  }
+}
+
+const fn saturate_usize(value: u64) -> usize {
+  if value as u128 > usize::MAX as u128 {
+    usize::MAX
+  } else {
+    value as usize
+  }
+}
+
+/**
+ * Checks at compile time that `REPRS` representations fit in a
+ * target with `CAPACITY`, failing with `message` otherwise. The
+ * compiler names both numbers when it reports the failure.
+ */
+pub struct FitsIn<const REPRS: usize, const CAPACITY: usize>;
+
+impl<const REPRS: usize, const CAPACITY: usize> FitsIn<REPRS, CAPACITY> {
+  pub const fn assert(message: &'static str) {
+    if REPRS > CAPACITY {
+      panic!("{}", message)
+    }
+  }
+}
@@ -1,6 +1,12 @@
 use std::convert::TryInto;
-use std::ops::{Add, Div, Mul, Rem, Sub};
-
+use std::ops::{Add, BitAnd, BitOr, Div, Mul, Rem, Shl, Shr, Sub};
+
+/**
+ * A number that values are encoded as. Encodings are always
+ * the numbers from zero up to but excluding `REPRS`, so signed
+ * types only use their non-negative values & negative numbers
+ * never decode.
+ */
 pub trait FiniteValue:
   Copy
   + Add<Self, Output = Self>
@@ -8,35 +14,95 @@ pub trait FiniteValue:
   + Mul<Self, Output = Self>
   + Rem<Self, Output = Self>
   + Sub<Self, Output = Self>
+  + BitAnd<Self, Output = Self>
+  + BitOr<Self, Output = Self>
+  + Shl<u32, Output = Self>
+  + Shr<u32, Output = Self>
   + PartialOrd
 {
+  /**
+   * The smallest number anything is encoded as, which is zero
+   * for signed types as well as unsigned ones.
+   */
   const MIN: Self;
   const ONE: Self;
   const ZERO: Self;
 
-  fn inc(self) -> Self {
-    self + Self::ONE
+  /**
+   * The largest value as a `usize`, or `usize::MAX` if the
+   * largest value is larger than any `usize`.
+   */
+  const MAX_USIZE: usize;
+
+  /**
+   * Fails rather than overflowing, like the arithmetic in
+   * every encoding.
+   */
+  fn inc(self) -> Option<Self> {
+    self.checked_add(Self::ONE)
   }
 
-  fn dec(self) -> Self {
-    self - Self::ONE
+  fn dec(self) -> Option<Self> {
+    self.checked_sub(Self::ONE)
   }
 
+  /**
+   * Fails when the sum is too large for this type, so encoding
+   * fails rather than panicking or wrapping.
+   */
+  fn checked_add(self, other: Self) -> Option<Self>;
+
+  /**
+   * Fails when the product is too large for this type.
+   */
+  fn checked_mul(self, other: Self) -> Option<Self>;
+
+  /**
+   * Fails when the difference is too small for this type.
+   */
+  fn checked_sub(self, other: Self) -> Option<Self>;
+
   fn from_usize(other: usize) -> Option<Self>;
   fn into_usize(other: Self) -> Option<usize>;
+
+  /**
+   * Materialises a constant that's no greater than `MAX_USIZE`,
+   * larger constants are truncated. Checking a constant against
+   * `MAX_USIZE` is free once monomorphised, which is how derived
+   * code avoids converting the same constant on every call.
+   */
+  fn constant(other: usize) -> Self;
 }
 
 impl FiniteValue for u8 {
   const MIN: u8 = 0;
   const ONE: u8 = 1;
   const ZERO: u8 = 0;
+  const MAX_USIZE: usize = u8::MAX as usize;
 
   fn from_usize(other: usize) -> Option<Self> {
     other.try_into().ok()
   }
 
   fn into_usize(other: Self) -> Option<usize> {
-    other.try_into().ok()
+    Some(other.into())
+  }
+
+  #[inline]
+  fn constant(other: usize) -> Self {
+    other as u8
+  }
+
+  fn checked_add(self, other: Self) -> Option<Self> {
+    u8::checked_add(self, other)
+  }
+
+  fn checked_mul(self, other: Self) -> Option<Self> {
+    u8::checked_mul(self, other)
+  }
+
+  fn checked_sub(self, other: Self) -> Option<Self> {
+    u8::checked_sub(self, other)
   }
 }
 
@@ -44,13 +110,31 @@ impl FiniteValue for u16 {
   const MIN: u16 = 0;
   const ONE: u16 = 1;
   const ZERO: u16 = 0;
+  const MAX_USIZE: usize = u16::MAX as usize;
 
   fn from_usize(other: usize) -> Option<Self> {
     other.try_into().ok()
   }
 
   fn into_usize(other: Self) -> Option<usize> {
-    other.try_into().ok()
+    Some(other.into())
+  }
+
+  #[inline]
+  fn constant(other: usize) -> Self {
+    other as u16
+  }
+
+  fn checked_add(self, other: Self) -> Option<Self> {
+    u16::checked_add(self, other)
+  }
+
+  fn checked_mul(self, other: Self) -> Option<Self> {
+    u16::checked_mul(self, other)
+  }
+
+  fn checked_sub(self, other: Self) -> Option<Self> {
+    u16::checked_sub(self, other)
   }
 }
 
@@ -58,6 +142,7 @@ impl FiniteValue for u32 {
   const MIN: u32 = 0;
   const ONE: u32 = 1;
   const ZERO: u32 = 0;
+  const MAX_USIZE: usize = saturate_usize(u32::MAX as u128);
 
   fn from_usize(other: usize) -> Option<Self> {
     other.try_into().ok()
@@ -66,12 +151,30 @@ impl FiniteValue for u32 {
   fn into_usize(other: Self) -> Option<usize> {
     other.try_into().ok()
   }
+
+  #[inline]
+  fn constant(other: usize) -> Self {
+    other as u32
+  }
+
+  fn checked_add(self, other: Self) -> Option<Self> {
+    u32::checked_add(self, other)
+  }
+
+  fn checked_mul(self, other: Self) -> Option<Self> {
+    u32::checked_mul(self, other)
+  }
+
+  fn checked_sub(self, other: Self) -> Option<Self> {
+    u32::checked_sub(self, other)
+  }
 }
 
 impl FiniteValue for u64 {
   const MIN: u64 = 0;
   const ONE: u64 = 1;
   const ZERO: u64 = 0;
+  const MAX_USIZE: usize = saturate_usize(u64::MAX as u128);
 
   fn from_usize(other: usize) -> Option<Self> {
     other.try_into().ok()
@@ -80,12 +183,30 @@ impl FiniteValue for u64 {
   fn into_usize(other: Self) -> Option<usize> {
     other.try_into().ok()
   }
+
+  #[inline]
+  fn constant(other: usize) -> Self {
+    other as u64
+  }
+
+  fn checked_add(self, other: Self) -> Option<Self> {
+    u64::checked_add(self, other)
+  }
+
+  fn checked_mul(self, other: Self) -> Option<Self> {
+    u64::checked_mul(self, other)
+  }
+
+  fn checked_sub(self, other: Self) -> Option<Self> {
+    u64::checked_sub(self, other)
+  }
 }
 
 impl FiniteValue for u128 {
   const MIN: u128 = 0;
   const ONE: u128 = 1;
   const ZERO: u128 = 0;
+  const MAX_USIZE: usize = saturate_usize(u128::MAX);
 
   fn from_usize(other: usize) -> Option<Self> {
     other.try_into().ok()
@@ -94,12 +215,62 @@ impl FiniteValue for u128 {
   fn into_usize(other: Self) -> Option<usize> {
     other.try_into().ok()
   }
+
+  #[inline]
+  fn constant(other: usize) -> Self {
+    other as u128
+  }
+
+  fn checked_add(self, other: Self) -> Option<Self> {
+    u128::checked_add(self, other)
+  }
+
+  fn checked_mul(self, other: Self) -> Option<Self> {
+    u128::checked_mul(self, other)
+  }
+
+  fn checked_sub(self, other: Self) -> Option<Self> {
+    u128::checked_sub(self, other)
+  }
+}
+
+impl FiniteValue for usize {
+  const MIN: usize = 0;
+  const ONE: usize = 1;
+  const ZERO: usize = 0;
+  const MAX_USIZE: usize = usize::MAX;
+
+  fn from_usize(other: usize) -> Option<Self> {
+    Some(other)
+  }
+
+  fn into_usize(other: Self) -> Option<usize> {
+    Some(other)
+  }
+
+  #[inline]
+  fn constant(other: usize) -> Self {
+    other
+  }
+
+  fn checked_add(self, other: Self) -> Option<Self> {
+    usize::checked_add(self, other)
+  }
+
+  fn checked_mul(self, other: Self) -> Option<Self> {
+    usize::checked_mul(self, other)
+  }
+
+  fn checked_sub(self, other: Self) -> Option<Self> {
+    usize::checked_sub(self, other)
+  }
 }
 
 impl FiniteValue for i8 {
-  const MIN: i8 = i8::MIN;
+  const MIN: i8 = 0;
   const ONE: i8 = 1;
   const ZERO: i8 = 0;
+  const MAX_USIZE: usize = i8::MAX as usize;
 
   fn from_usize(other: usize) -> Option<Self> {
     other.try_into().ok()
@@ -108,12 +279,30 @@ impl FiniteValue for i8 {
   fn into_usize(other: Self) -> Option<usize> {
     other.try_into().ok()
   }
+
+  #[inline]
+  fn constant(other: usize) -> Self {
+    other as i8
+  }
+
+  fn checked_add(self, other: Self) -> Option<Self> {
+    i8::checked_add(self, other)
+  }
+
+  fn checked_mul(self, other: Self) -> Option<Self> {
+    i8::checked_mul(self, other)
+  }
+
+  fn checked_sub(self, other: Self) -> Option<Self> {
+    i8::checked_sub(self, other)
+  }
 }
 
 impl FiniteValue for i16 {
-  const MIN: i16 = i16::MIN;
+  const MIN: i16 = 0;
   const ONE: i16 = 1;
   const ZERO: i16 = 0;
+  const MAX_USIZE: usize = i16::MAX as usize;
 
   fn from_usize(other: usize) -> Option<Self> {
     other.try_into().ok()
@@ -122,12 +311,30 @@ impl FiniteValue for i16 {
   fn into_usize(other: Self) -> Option<usize> {
     other.try_into().ok()
   }
+
+  #[inline]
+  fn constant(other: usize) -> Self {
+    other as i16
+  }
+
+  fn checked_add(self, other: Self) -> Option<Self> {
+    i16::checked_add(self, other)
+  }
+
+  fn checked_mul(self, other: Self) -> Option<Self> {
+    i16::checked_mul(self, other)
+  }
+
+  fn checked_sub(self, other: Self) -> Option<Self> {
+    i16::checked_sub(self, other)
+  }
 }
 
 impl FiniteValue for i32 {
-  const MIN: i32 = i32::MIN;
+  const MIN: i32 = 0;
   const ONE: i32 = 1;
   const ZERO: i32 = 0;
+  const MAX_USIZE: usize = saturate_usize(i32::MAX as u128);
 
   fn from_usize(other: usize) -> Option<Self> {
     other.try_into().ok()
@@ -136,12 +343,30 @@ impl FiniteValue for i32 {
   fn into_usize(other: Self) -> Option<usize> {
     other.try_into().ok()
   }
+
+  #[inline]
+  fn constant(other: usize) -> Self {
+    other as i32
+  }
+
+  fn checked_add(self, other: Self) -> Option<Self> {
+    i32::checked_add(self, other)
+  }
+
+  fn checked_mul(self, other: Self) -> Option<Self> {
+    i32::checked_mul(self, other)
+  }
+
+  fn checked_sub(self, other: Self) -> Option<Self> {
+    i32::checked_sub(self, other)
+  }
 }
 
 impl FiniteValue for i64 {
-  const MIN: i64 = i64::MIN;
+  const MIN: i64 = 0;
   const ONE: i64 = 1;
   const ZERO: i64 = 0;
+  const MAX_USIZE: usize = saturate_usize(i64::MAX as u128);
 
   fn from_usize(other: usize) -> Option<Self> {
     other.try_into().ok()
@@ -150,12 +375,30 @@ impl FiniteValue for i64 {
   fn into_usize(other: Self) -> Option<usize> {
     other.try_into().ok()
   }
+
+  #[inline]
+  fn constant(other: usize) -> Self {
+    other as i64
+  }
+
+  fn checked_add(self, other: Self) -> Option<Self> {
+    i64::checked_add(self, other)
+  }
+
+  fn checked_mul(self, other: Self) -> Option<Self> {
+    i64::checked_mul(self, other)
+  }
+
+  fn checked_sub(self, other: Self) -> Option<Self> {
+    i64::checked_sub(self, other)
+  }
 }
 
 impl FiniteValue for i128 {
-  const MIN: i128 = i128::MIN;
+  const MIN: i128 = 0;
   const ONE: i128 = 1;
   const ZERO: i128 = 0;
+  const MAX_USIZE: usize = saturate_usize(i128::MAX as u128);
 
   fn from_usize(other: usize) -> Option<Self> {
     other.try_into().ok()
@@ -164,4 +407,61 @@ impl FiniteValue for i128 {
   fn into_usize(other: Self) -> Option<usize> {
     other.try_into().ok()
   }
+
+  #[inline]
+  fn constant(other: usize) -> Self {
+    other as i128
+  }
+
+  fn checked_add(self, other: Self) -> Option<Self> {
+    i128::checked_add(self, other)
+  }
+
+  fn checked_mul(self, other: Self) -> Option<Self> {
+    i128::checked_mul(self, other)
+  }
+
+  fn checked_sub(self, other: Self) -> Option<Self> {
+    i128::checked_sub(self, other)
+  }
+}
+
+impl FiniteValue for isize {
+  const MIN: isize = 0;
+  const ONE: isize = 1;
+  const ZERO: isize = 0;
+  const MAX_USIZE: usize = isize::MAX as usize;
+
+  fn from_usize(other: usize) -> Option<Self> {
+    other.try_into().ok()
+  }
+
+  fn into_usize(other: Self) -> Option<usize> {
+    other.try_into().ok()
+  }
+
+  #[inline]
+  fn constant(other: usize) -> Self {
+    other as isize
+  }
+
+  fn checked_add(self, other: Self) -> Option<Self> {
+    isize::checked_add(self, other)
+  }
+
+  fn checked_mul(self, other: Self) -> Option<Self> {
+    isize::checked_mul(self, other)
+  }
+
+  fn checked_sub(self, other: Self) -> Option<Self> {
+    isize::checked_sub(self, other)
+  }
+}
+
+const fn saturate_usize(value: u128) -> usize {
+  if value > usize::MAX as u128 {
+    usize::MAX
+  } else {
+    value as usize
+  }
 }
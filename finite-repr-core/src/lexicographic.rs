@@ -0,0 +1,135 @@
+use crate::decode_error::DecodeError;
+use crate::finite_value::FiniteValue;
+use crate::repr::FiniteRepr;
+use crate::traits::{nested_error, place, split, FiniteDecoding, FiniteEncoding};
+use core::cmp::Ordering;
+
+/**
+ * Encodes a tuple with its first element as the most significant
+ * digit, rather than the least, so numbers are ordered the same
+ * as the tuples they encode. This matches the derived encoding
+ * of a struct with `#[finite(order = "lexicographic")]`.
+ *
+ * `Ord` compares each element by its encoding rather than its own
+ * `Ord`, which keeps it in step with the numbers for elements such
+ * as options, which encode `None` last.
+ */
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default)]
+pub struct Lexicographic<T>(pub T);
+
+impl<A: FiniteEncoding + Eq, B: FiniteEncoding + Eq> Ord for Lexicographic<(A, B)> {
+  fn cmp(&self, other: &Self) -> Ordering {
+    self.finite_cmp(other)
+  }
+}
+
+impl<A: FiniteEncoding + Eq, B: FiniteEncoding + Eq> PartialOrd for Lexicographic<(A, B)> {
+  fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+    Some(self.cmp(other))
+  }
+}
+
+impl<A, B, C> Ord for Lexicographic<(A, B, C)>
+where
+  A: FiniteEncoding + Eq,
+  B: FiniteEncoding + Eq,
+  C: FiniteEncoding + Eq,
+{
+  fn cmp(&self, other: &Self) -> Ordering {
+    self.finite_cmp(other)
+  }
+}
+
+impl<A, B, C> PartialOrd for Lexicographic<(A, B, C)>
+where
+  A: FiniteEncoding + Eq,
+  B: FiniteEncoding + Eq,
+  C: FiniteEncoding + Eq,
+{
+  fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+    Some(self.cmp(other))
+  }
+}
+
+impl<A: FiniteRepr, B: FiniteRepr> FiniteRepr for Lexicographic<(A, B)> {
+  const REPRS: usize = A::REPRS * B::REPRS;
+}
+
+impl<A: FiniteRepr, B: FiniteRepr, C: FiniteRepr> FiniteRepr for Lexicographic<(A, B, C)> {
+  const REPRS: usize = A::REPRS * B::REPRS * C::REPRS;
+}
+
+impl<A: FiniteEncoding, B: FiniteEncoding> FiniteEncoding for Lexicographic<(A, B)> {
+  fn into_finite<T: FiniteValue>(&self) -> Option<T> {
+    let Lexicographic((a, b)) = self;
+    let a_num = place(a.into_finite::<T>()?, B::REPRS)?;
+    let b_num = b.into_finite::<T>()?;
+    a_num.checked_add(b_num)
+  }
+
+  fn finite_cmp(&self, other: &Self) -> Ordering {
+    let (Lexicographic((a, b)), Lexicographic((other_a, other_b))) = (self, other);
+    a.finite_cmp(other_a).then_with(|| b.finite_cmp(other_b))
+  }
+}
+
+impl<A: FiniteDecoding, B: FiniteDecoding> FiniteDecoding for Lexicographic<(A, B)> {
+  fn from_finite<T: FiniteValue>(number: T) -> Option<Self> {
+    let (b_num, a_num) = split(number, B::REPRS);
+    Some(Lexicographic((
+      A::from_finite(a_num)?,
+      B::from_finite(b_num)?,
+    )))
+  }
+
+  fn field_error<T: FiniteValue>(number: T) -> DecodeError {
+    let (b_num, a_num) = split(number, B::REPRS);
+    nested_error([
+      A::try_from_finite(a_num).err(),
+      B::try_from_finite(b_num).err(),
+    ])
+  }
+}
+
+impl<A: FiniteEncoding, B: FiniteEncoding, C: FiniteEncoding> FiniteEncoding
+  for Lexicographic<(A, B, C)>
+{
+  fn into_finite<T: FiniteValue>(&self) -> Option<T> {
+    let Lexicographic((a, b, c)) = self;
+    let a_num = place(a.into_finite::<T>()?, B::REPRS * C::REPRS)?;
+    let b_num = place(b.into_finite::<T>()?, C::REPRS)?;
+    let c_num = c.into_finite::<T>()?;
+    a_num.checked_add(b_num)?.checked_add(c_num)
+  }
+
+  fn finite_cmp(&self, other: &Self) -> Ordering {
+    let (Lexicographic((a, b, c)), Lexicographic((other_a, other_b, other_c))) = (self, other);
+    a.finite_cmp(other_a)
+      .then_with(|| b.finite_cmp(other_b))
+      .then_with(|| c.finite_cmp(other_c))
+  }
+}
+
+impl<A: FiniteDecoding, B: FiniteDecoding, C: FiniteDecoding> FiniteDecoding
+  for Lexicographic<(A, B, C)>
+{
+  fn from_finite<T: FiniteValue>(number: T) -> Option<Self> {
+    let (c_num, rest) = split(number, C::REPRS);
+    let (b_num, a_num) = split(rest, B::REPRS);
+    Some(Lexicographic((
+      A::from_finite(a_num)?,
+      B::from_finite(b_num)?,
+      C::from_finite(c_num)?,
+    )))
+  }
+
+  fn field_error<T: FiniteValue>(number: T) -> DecodeError {
+    let (c_num, rest) = split(number, C::REPRS);
+    let (b_num, a_num) = split(rest, B::REPRS);
+    nested_error([
+      A::try_from_finite(a_num).err(),
+      B::try_from_finite(b_num).err(),
+      C::try_from_finite(c_num).err(),
+    ])
+  }
+}
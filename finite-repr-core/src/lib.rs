@@ -1,15 +1,31 @@
 /**
  * This create exists to allow the encoding of data
  */
-mod finite_repr;
+// Lets code derived inside this crate name it as `::finite_repr`.
+extern crate self as finite_repr;
+
+mod decode_error;
+mod finite_compact;
+mod finite_target;
 mod finite_value;
+mod lexicographic;
+mod overflow;
+mod repr;
 mod traits;
 
-pub use self::finite_repr::FiniteRepr;
+pub use self::decode_error::DecodeError;
+pub use self::finite_compact::{CompactWidth, FiniteCompact, Smallest, Width};
+pub use self::finite_target::{BigEndian, FiniteTarget, FitsIn, JsSafeInt, LittleEndian};
 pub use self::finite_value::FiniteValue;
-pub use self::traits::{FiniteDecoding, FiniteEncoding};
+pub use self::lexicographic::Lexicographic;
+pub use self::overflow::Overflow;
+pub use self::repr::{bits_required, bytes_required, FiniteRepr};
+pub use self::traits::{
+  FiniteDecoding, FiniteEncoding, FiniteFallback, FiniteTagged, FiniteUnion, FiniteUnionDecoding,
+  FiniteUnionEncoding,
+};
 
-// Re-export #[derive(FiniteRepr, FiniteEncoding, FiniteDecoding)].
+// Re-export #[derive(Finite, FiniteRepr, FiniteEncoding, FiniteDecoding, FiniteOrd, FiniteCompact)].
 #[cfg(feature = "finite_repr_derive")]
 #[allow(unused_imports)]
 #[macro_use]
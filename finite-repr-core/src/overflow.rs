@@ -0,0 +1,42 @@
+use crate::finite_value::FiniteValue;
+
+/**
+ * What encoding does with a number too large for the target,
+ * used by `FiniteEncoding::into_finite_with`. Decoding with
+ * `FiniteDecoding::from_finite_with` treats numbers at or beyond
+ * `REPRS` the same way.
+ */
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Overflow {
+  /**
+   * Fails, the same as `FiniteEncoding::into_finite` or
+   * `FiniteDecoding::from_finite`.
+   */
+  Checked,
+  /**
+   * Encodes as the largest number of the target, or decodes
+   * as the last value of the type.
+   */
+  Saturating,
+  /**
+   * Encodes as the number modulo the range of the target, for
+   * uses such as hash bucketing or sharding, or decodes the
+   * number modulo `REPRS`.
+   */
+  Wrapping,
+}
+
+impl Overflow {
+  /**
+   * Fits `number`, the exact encoding of a value, into `T`.
+   * Signed targets only have their non-negative range.
+   */
+  pub(crate) fn apply<T: FiniteValue>(self, number: usize) -> Option<T> {
+    match self {
+      _ if number <= T::MAX_USIZE => T::from_usize(number),
+      Overflow::Checked => None,
+      Overflow::Saturating => T::from_usize(T::MAX_USIZE),
+      Overflow::Wrapping => T::from_usize(number % (T::MAX_USIZE + 1)),
+    }
+  }
+}
@@ -0,0 +1,87 @@
+pub trait FiniteRepr {
+  /**
+   * The possible permutations for this value.
+   */
+  const REPRS: usize;
+
+  /**
+   * The representations in use, which is fewer than `REPRS` when
+   * a derived type reserves some with `#[finite(reserve = N)]`.
+   * Numbers from this up to `REPRS` are reserved.
+   */
+  const USED_REPRS: usize = Self::REPRS;
+
+  /**
+   * The number of bits needed to store every representation.
+   */
+  const BITS_REQUIRED: u32 = bits_required(Self::REPRS);
+
+  /**
+   * The number of whole bytes needed to store every representation.
+   */
+  const BYTES_REQUIRED: usize = bytes_required(Self::REPRS);
+}
+
+/**
+ * The number of bits needed to store every number from zero
+ * up to but excluding `reprs`.
+ */
+#[inline]
+pub const fn bits_required(reprs: usize) -> u32 {
+  match reprs {
+    0 | 1 => 0,
+    reprs => usize::BITS - (reprs - 1).leading_zeros(),
+  }
+}
+
+/**
+ * The number of whole bytes needed to store every number
+ * from zero up to but excluding `reprs`.
+ */
+#[inline]
+pub const fn bytes_required(reprs: usize) -> usize {
+  (bits_required(reprs) as usize).div_ceil(8)
+}
+
+impl FiniteRepr for u8 {
+  const REPRS: usize = u8::MAX as usize + 1;
+}
+
+impl FiniteRepr for u16 {
+  const REPRS: usize = u16::MAX as usize + 1;
+}
+
+// Every `u32` is only a representation on targets where a
+// `usize` holds one more than `u32::MAX`.
+#[cfg(target_pointer_width = "64")]
+impl FiniteRepr for u32 {
+  const REPRS: usize = u32::MAX as usize + 1;
+}
+
+impl FiniteRepr for i8 {
+  const REPRS: usize = u8::MAX as usize + 1;
+}
+
+impl FiniteRepr for i16 {
+  const REPRS: usize = u16::MAX as usize + 1;
+}
+
+impl FiniteRepr for bool {
+  const REPRS: usize = 2;
+}
+
+impl<A: FiniteRepr, B: FiniteRepr> FiniteRepr for (A, B) {
+  const REPRS: usize = A::REPRS * B::REPRS;
+}
+
+impl<A: FiniteRepr, B: FiniteRepr, C: FiniteRepr> FiniteRepr for (A, B, C) {
+  const REPRS: usize = A::REPRS * B::REPRS * C::REPRS;
+}
+
+impl<A: FiniteRepr> FiniteRepr for Option<A> {
+  const REPRS: usize = 1 + A::REPRS;
+}
+
+impl<A: FiniteRepr, B: FiniteRepr> FiniteRepr for Result<A, B> {
+  const REPRS: usize = A::REPRS + B::REPRS;
+}
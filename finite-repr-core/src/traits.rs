@@ -1,16 +1,77 @@
-use crate::finite_repr::FiniteRepr;
+use crate::decode_error::DecodeError;
+use crate::finite_target::FiniteTarget;
 use crate::finite_value::FiniteValue;
+use crate::overflow::Overflow;
+use crate::repr::FiniteRepr;
+use core::cmp::Ordering;
 
 /**
  * Encodes values that implement this trait into values
  * that implement `FiniteValue` (mostly numbers).
+ *
+ * ```
+ * use finite_repr::{FiniteDecoding, FiniteEncoding};
+ * use finite_repr_derive::Finite;
+ *
+ * #[derive(Clone, Copy, PartialEq, Debug, Finite)]
+ * enum Shape {
+ *   Empty,
+ *   Square(bool),
+ * }
+ *
+ * assert_eq!(Shape::Square(true).into_finite::<u8>(), Some(2));
+ * assert_eq!(Shape::from_finite(2u8), Some(Shape::Square(true)));
+ * ```
  */
 pub trait FiniteEncoding: FiniteRepr {
   /**
    * The implementation of this method should be isomorphic
    * to the implementation of `FiniteDecoding::from_finite`.
    */
+  #[allow(clippy::wrong_self_convention)]
   fn into_finite<T: FiniteValue>(&self) -> Option<T>;
+
+  /**
+   * Encodes into a target such as `NonZeroU32`, by way of
+   * the number it's converted from.
+   */
+  #[allow(clippy::wrong_self_convention)]
+  fn into_finite_target<T: FiniteTarget>(&self) -> Option<T> {
+    const {
+      assert!(
+        Self::REPRS <= T::MAX_REPRS,
+        "too many representations for the target"
+      )
+    };
+    T::from_value(self.into_finite::<T::Value>()?)
+  }
+
+  /**
+   * Encodes into `T` with an explicit policy for numbers too
+   * large for it. The whole number is encoded before the policy
+   * is applied, so nested types overflow the same way as their
+   * containers. Numbers that fit in `T` encode the same with
+   * every policy.
+   */
+  #[allow(clippy::wrong_self_convention)]
+  fn into_finite_with<T: FiniteValue>(&self, overflow: Overflow) -> Option<T> {
+    match overflow {
+      Overflow::Checked => self.into_finite::<T>(),
+      _ => overflow.apply(self.into_finite::<usize>()?),
+    }
+  }
+
+  /**
+   * Compares two values the same as the numbers they encode as.
+   * Types that know their order override this so neither value
+   * is encoded, which `#[derive(FiniteOrd)]` relies on for fields.
+   */
+  #[doc(hidden)]
+  fn finite_cmp(&self, other: &Self) -> Ordering {
+    self
+      .into_finite::<usize>()
+      .cmp(&other.into_finite::<usize>())
+  }
 }
 
 /**
@@ -23,11 +84,201 @@ pub trait FiniteDecoding: FiniteRepr + Sized {
    * to the implementation of `FiniteEncoding::into_finite`.
    */
   fn from_finite<T: FiniteValue>(number: T) -> Option<Self>;
+
+  /**
+   * Decodes from a target such as `NonZeroU32`, the inverse
+   * of `FiniteEncoding::into_finite_target`.
+   */
+  fn from_finite_target<T: FiniteTarget>(target: T) -> Option<Self> {
+    const {
+      assert!(
+        Self::REPRS <= T::MAX_REPRS,
+        "too many representations for the target"
+      )
+    };
+    Self::from_finite(target.into_value()?)
+  }
+
+  /**
+   * Decodes with an explicit policy for numbers at or beyond
+   * `REPRS`, for reading data that may be out of range.
+   * `Overflow::Saturating` clamps them, & any reserved numbers,
+   * to the last value in use. `Overflow::Wrapping` reduces them
+   * modulo `REPRS`.
+   */
+  fn from_finite_with<T: FiniteValue>(number: T, overflow: Overflow) -> Option<Self> {
+    match overflow {
+      Overflow::Checked => Self::from_finite(number),
+      Overflow::Saturating => Self::from_finite(clamp(number, Self::USED_REPRS)),
+      Overflow::Wrapping => Self::from_finite(wrap(number, Self::REPRS)),
+    }
+  }
+
+  /**
+   * Like `from_finite`, but reports why a number doesn't decode,
+   * distinguishing numbers reserved by `#[finite(reserve = N)]`.
+   */
+  fn try_from_finite<T: FiniteValue>(number: T) -> Result<Self, DecodeError> {
+    if let Some(value) = Self::from_finite(number) {
+      return Ok(value);
+    }
+
+    match T::into_usize(number) {
+      Some(index) if index < Self::USED_REPRS => Err(Self::field_error(number)),
+      Some(index) if index < Self::REPRS => Err(DecodeError::Reserved),
+      _ => Err(DecodeError::OutOfRange),
+    }
+  }
+
+  /**
+   * Why a number below `USED_REPRS` doesn't decode. Types with
+   * fields report the error of the field rejecting its digit,
+   * so a number reserved by a nested type is still `Reserved`.
+   */
+  #[doc(hidden)]
+  fn field_error<T: FiniteValue>(_number: T) -> DecodeError {
+    DecodeError::Invalid
+  }
+
+  /**
+   * Decodes the fallback value of the type from any number
+   * that doesn't decode, such as one at or beyond `REPRS`.
+   */
+  fn from_finite_or_fallback<T: FiniteValue>(number: T) -> Self
+  where
+    Self: FiniteFallback,
+  {
+    Self::from_finite(number).unwrap_or_else(Self::fallback)
+  }
+}
+
+/**
+ * Numbers no smaller than `reprs` become the last of them, a
+ * type too small to hold `reprs` has no such numbers.
+ */
+fn clamp<T: FiniteValue>(number: T, reprs: usize) -> T {
+  if reprs == 0 || reprs - 1 > T::MAX_USIZE {
+    return number;
+  }
+
+  let last = T::constant(reprs - 1);
+  if number > last {
+    last
+  } else {
+    number
+  }
+}
+
+/**
+ * Reduces `number` modulo `reprs`, a type too small to hold
+ * `reprs` has nothing to reduce.
+ */
+fn wrap<T: FiniteValue>(number: T, reprs: usize) -> T {
+  if reprs == 0 || reprs > T::MAX_USIZE {
+    return number;
+  }
+
+  number % T::constant(reprs)
+}
+
+/**
+ * A value substituted for numbers that don't decode, derived
+ * for an enum by marking a variant `#[finite(fallback)]`.
+ */
+pub trait FiniteFallback: Sized {
+  fn fallback() -> Self;
+}
+
+/**
+ * A union whose initialised arm is identified by a tag stored
+ * outside of it, typically in a sibling field of a struct.
+ */
+pub trait FiniteUnion: FiniteRepr {
+  type Tag;
+}
+
+/**
+ * Implemented by types holding a tagged union, to promise the
+ * fields naming each union's tag always identify its initialised
+ * arm. Deriving `FiniteEncoding` for such a type reads the arm the
+ * tag identifies, so it requires this trait.
+ *
+ * ```
+ * use finite_repr::{Finite, FiniteEncoding, FiniteTagged};
+ *
+ * #[derive(Clone, Copy, Finite)]
+ * #[finite(tag = u8)]
+ * union Payload {
+ *   #[finite(tag = 0)]
+ *   flag: bool,
+ *   #[finite(tag = 1)]
+ *   byte: u8,
+ * }
+ *
+ * #[derive(Clone, Copy, Finite)]
+ * struct Message {
+ *   kind: u8,
+ *   #[finite(tag = kind)]
+ *   payload: Payload,
+ * }
+ *
+ * // SAFETY: `Message` is only built with `kind` matching the arm
+ * // of `payload` that's initialised.
+ * unsafe impl FiniteTagged for Message {}
+ *
+ * let message = Message { kind: 1, payload: Payload { byte: 3 } };
+ * assert_eq!(message.into_finite::<u16>(), Some(5));
+ * ```
+ *
+ * # Safety
+ *
+ * Every value of the type must store, in each field named by a
+ * `#[finite(tag = field)]`, the tag of the arm initialised in the
+ * union that attribute is on, or a tag of no arm, which fails to
+ * encode.
+ */
+#[diagnostic::on_unimplemented(
+  message = "`{Self}` holds a tagged union, so it needs `unsafe impl FiniteTagged`",
+  note = "the impl promises each tag field identifies the initialised arm of its union"
+)]
+pub unsafe trait FiniteTagged {}
+
+/**
+ * Encodes a union into values that implement `FiniteValue`,
+ * treating each arm as the variant of a sum.
+ */
+pub trait FiniteUnionEncoding: FiniteUnion {
+  /**
+   * The implementation of this method should be isomorphic
+   * to the implementation of `FiniteUnionDecoding::from_finite_arm`.
+   *
+   * # Safety
+   *
+   * `tag` must identify the arm of `self` that is initialised.
+   */
+  #[allow(clippy::wrong_self_convention)]
+  unsafe fn into_finite_arm<T: FiniteValue>(&self, tag: &Self::Tag) -> Option<T>;
+}
+
+/**
+ * Decodes values that implement `FiniteValue` into a union,
+ * along with the tag identifying the arm that was initialised.
+ */
+pub trait FiniteUnionDecoding: FiniteUnion + Sized {
+  /**
+   * The implementation of this method should be isomorphic
+   * to the implementation of `FiniteUnionEncoding::into_finite_arm`.
+   */
+  fn from_finite_arm<T: FiniteValue>(number: T) -> Option<(Self::Tag, Self)>;
 }
 
 impl FiniteEncoding for bool {
   fn into_finite<T: FiniteValue>(&self) -> Option<T> {
-    Some(if *self { T::ONE } else { T::MIN })
+    Some(if *self { T::ONE } else { T::ZERO })
+  }
+
+  fn finite_cmp(&self, other: &Self) -> Ordering {
+    self.cmp(other)
   }
 }
 
@@ -35,7 +286,7 @@ impl FiniteDecoding for bool {
   fn from_finite<T: FiniteValue>(number: T) -> Option<Self> {
     match number {
       v if v == T::ONE => Some(true),
-      v if v == T::MIN => Some(false),
+      v if v == T::ZERO => Some(false),
       _ => None,
     }
   }
@@ -45,6 +296,10 @@ impl FiniteEncoding for u8 {
   fn into_finite<T: FiniteValue>(&self) -> Option<T> {
     T::from_usize(u8::into_usize(*self)?)
   }
+
+  fn finite_cmp(&self, other: &Self) -> Ordering {
+    self.cmp(other)
+  }
 }
 
 impl FiniteDecoding for u8 {
@@ -53,96 +308,253 @@ impl FiniteDecoding for u8 {
   }
 }
 
+impl FiniteEncoding for u16 {
+  fn into_finite<T: FiniteValue>(&self) -> Option<T> {
+    T::from_usize(u16::into_usize(*self)?)
+  }
+
+  fn finite_cmp(&self, other: &Self) -> Ordering {
+    self.cmp(other)
+  }
+}
+
+impl FiniteDecoding for u16 {
+  fn from_finite<T: FiniteValue>(number: T) -> Option<Self> {
+    u16::from_usize(T::into_usize(number)?)
+  }
+}
+
+#[cfg(target_pointer_width = "64")]
+impl FiniteEncoding for u32 {
+  fn into_finite<T: FiniteValue>(&self) -> Option<T> {
+    T::from_usize(u32::into_usize(*self)?)
+  }
+
+  fn finite_cmp(&self, other: &Self) -> Ordering {
+    self.cmp(other)
+  }
+}
+
+#[cfg(target_pointer_width = "64")]
+impl FiniteDecoding for u32 {
+  fn from_finite<T: FiniteValue>(number: T) -> Option<Self> {
+    u32::from_usize(T::into_usize(number)?)
+  }
+}
+
 impl<A: FiniteEncoding> FiniteEncoding for Option<A> {
   fn into_finite<T: FiniteValue>(&self) -> Option<T> {
     match self {
       Some(a) => a.into_finite(),
-      None => T::from_usize(A::REPRS).map(|v| v.inc()),
+      None => T::from_usize(A::REPRS),
+    }
+  }
+
+  fn finite_cmp(&self, other: &Self) -> Ordering {
+    match (self, other) {
+      (Some(a), Some(other_a)) => a.finite_cmp(other_a),
+      (Some(_), None) => Ordering::Less,
+      (None, Some(_)) => Ordering::Greater,
+      (None, None) => Ordering::Equal,
     }
   }
 }
 
 impl<A: FiniteDecoding> FiniteDecoding for Option<A> {
   fn from_finite<T: FiniteValue>(number: T) -> Option<Self> {
-    let a_size = T::from_usize(A::REPRS)?;
+    // A size too large for the number is larger than every
+    // number, so each of them is a value of `A`.
+    let a_size = match T::from_usize(A::REPRS) {
+      Some(a_size) => a_size,
+      None => return A::from_finite(number).map(Some),
+    };
 
     match number {
-      value if value <= a_size => A::from_finite(value).map(Some),
-      value if value == a_size.inc() => Some(None),
+      value if value < a_size => A::from_finite(value).map(Some),
+      value if value == a_size => Some(None),
       _ => None,
     }
   }
+
+  fn field_error<T: FiniteValue>(number: T) -> DecodeError {
+    nested_error([A::try_from_finite(number).err()])
+  }
 }
 
 impl<A: FiniteEncoding, B: FiniteEncoding> FiniteEncoding for (A, B) {
   fn into_finite<T: FiniteValue>(&self) -> Option<T> {
     let (a, b) = self;
     let a_num = a.into_finite::<T>()?;
-    let b_num = T::from_usize(A::REPRS)? * b.into_finite::<T>()?;
-    Some(a_num + b_num)
+    let b_num = place(b.into_finite::<T>()?, A::REPRS)?;
+    a_num.checked_add(b_num)
+  }
+
+  fn finite_cmp(&self, other: &Self) -> Ordering {
+    let ((a, b), (other_a, other_b)) = (self, other);
+    b.finite_cmp(other_b).then_with(|| a.finite_cmp(other_a))
   }
 }
 
 impl<A: FiniteDecoding, B: FiniteDecoding> FiniteDecoding for (A, B) {
   fn from_finite<T: FiniteValue>(number: T) -> Option<Self> {
-    let a_size = T::from_usize(A::REPRS)?;
-    let a = A::from_finite(number % a_size)?;
-    let b = B::from_finite(number / a_size)?;
-    Some((a, b))
+    let (a_num, b_num) = split(number, A::REPRS);
+    Some((A::from_finite(a_num)?, B::from_finite(b_num)?))
+  }
+
+  fn field_error<T: FiniteValue>(number: T) -> DecodeError {
+    let (a_num, b_num) = split(number, A::REPRS);
+    nested_error([
+      A::try_from_finite(a_num).err(),
+      B::try_from_finite(b_num).err(),
+    ])
   }
 }
 
 impl<A: FiniteEncoding, B: FiniteEncoding, C: FiniteEncoding> FiniteEncoding for (A, B, C) {
   fn into_finite<T: FiniteValue>(&self) -> Option<T> {
     let (a, b, c) = self;
-    let a_size = T::from_usize(A::REPRS)?;
-    let b_size = T::from_usize(B::REPRS)?;
-
     let a_num = a.into_finite::<T>()?;
-    let b_num = a_size * b.into_finite::<T>()?;
-    let c_num = a_size * b_size * c.into_finite::<T>()?;
-    Some(a_num + b_num + c_num)
+    let b_num = place(b.into_finite::<T>()?, A::REPRS)?;
+    let c_num = place(c.into_finite::<T>()?, A::REPRS * B::REPRS)?;
+    a_num.checked_add(b_num)?.checked_add(c_num)
+  }
+
+  fn finite_cmp(&self, other: &Self) -> Ordering {
+    let ((a, b, c), (other_a, other_b, other_c)) = (self, other);
+    c.finite_cmp(other_c)
+      .then_with(|| b.finite_cmp(other_b))
+      .then_with(|| a.finite_cmp(other_a))
   }
 }
 
 impl<A: FiniteDecoding, B: FiniteDecoding, C: FiniteDecoding> FiniteDecoding for (A, B, C) {
   fn from_finite<T: FiniteValue>(number: T) -> Option<Self> {
-    let a_size = T::from_usize(A::REPRS)?;
-    let b_size = T::from_usize(B::REPRS)?;
+    let (a_num, rest) = split(number, A::REPRS);
+    let (b_num, c_num) = split(rest, B::REPRS);
+    Some((
+      A::from_finite(a_num)?,
+      B::from_finite(b_num)?,
+      C::from_finite(c_num)?,
+    ))
+  }
 
-    let a = A::from_finite(number % a_size)?;
-    let b = B::from_finite((number / a_size) % b_size)?;
-    let c = C::from_finite(number / (a_size * b_size))?;
-    Some((a, b, c))
+  fn field_error<T: FiniteValue>(number: T) -> DecodeError {
+    let (a_num, rest) = split(number, A::REPRS);
+    let (b_num, c_num) = split(rest, B::REPRS);
+    nested_error([
+      A::try_from_finite(a_num).err(),
+      B::try_from_finite(b_num).err(),
+      C::try_from_finite(c_num).err(),
+    ])
   }
 }
 
 impl<A: FiniteEncoding, B: FiniteEncoding> FiniteEncoding for Result<A, B> {
   fn into_finite<T: FiniteValue>(&self) -> Option<T> {
-    let a_size = T::from_usize(A::REPRS)?;
     match self {
       Ok(a) => a.into_finite(),
-      Err(b) => Some(a_size.inc() + b.into_finite()?),
+      Err(b) => T::from_usize(A::REPRS)?.checked_add(b.into_finite()?),
+    }
+  }
+
+  fn finite_cmp(&self, other: &Self) -> Ordering {
+    match (self, other) {
+      (Ok(a), Ok(other_a)) => a.finite_cmp(other_a),
+      (Ok(_), Err(_)) => Ordering::Less,
+      (Err(_), Ok(_)) => Ordering::Greater,
+      (Err(b), Err(other_b)) => b.finite_cmp(other_b),
     }
   }
 }
 
 impl<A: FiniteDecoding, B: FiniteDecoding> FiniteDecoding for Result<A, B> {
   fn from_finite<T: FiniteValue>(number: T) -> Option<Self> {
-    let usize_v = T::into_usize(number)?;
-    let a_size = T::from_usize(A::REPRS)?;
+    // Like an option, every number is a value of `A` when its
+    // size is too large for the number.
+    match T::from_usize(A::REPRS) {
+      Some(a_size) if number >= a_size => B::from_finite(number.checked_sub(a_size)?).map(Err),
+      _ => A::from_finite(number).map(Ok),
+    }
+  }
 
-    match number {
-      value if usize_v <= A::REPRS => A::from_finite(value).map(Ok),
-      value => B::from_finite(value.dec() - a_size).map(Err),
+  fn field_error<T: FiniteValue>(number: T) -> DecodeError {
+    match T::from_usize(A::REPRS) {
+      Some(a_size) if number >= a_size => nested_error([B::try_from_finite(number - a_size).err()]),
+      _ => nested_error([A::try_from_finite(number).err()]),
     }
   }
 }
 
+/**
+ * The error of a number rejected by the fields given each of
+ * their errors. It's only reserved if every field rejecting it
+ * reserves it, as the number is never valid otherwise.
+ */
+pub(crate) fn nested_error<const N: usize>(errors: [Option<DecodeError>; N]) -> DecodeError {
+  let mut errors = errors.iter().flatten();
+  let rejected = errors.clone().next().is_some();
+  match rejected && errors.all(|error| *error == DecodeError::Reserved) {
+    true => DecodeError::Reserved,
+    false => DecodeError::Invalid,
+  }
+}
+
+/**
+ * The digit `number` at the place value `place`, which is only
+ * required to fit in `T` when the digit isn't zero.
+ */
+pub(crate) fn place<T: FiniteValue>(number: T, place: usize) -> Option<T> {
+  if number == T::ZERO {
+    return Some(T::ZERO);
+  }
+
+  T::from_usize(place)?.checked_mul(number)
+}
+
+/**
+ * The remainder & quotient of `number` divided by `radix`. A
+ * radix too large for `T` is larger than every number, leaving
+ * all of it as the remainder.
+ */
+pub(crate) fn split<T: FiniteValue>(number: T, radix: usize) -> (T, T) {
+  match T::from_usize(radix) {
+    Some(radix) => (number % radix, number / radix),
+    None => (number, T::ZERO),
+  }
+}
+
 #[cfg(test)]
 mod tests {
   use super::*;
 
+  #[test]
+  fn test_derive_inside_crate() {
+    #[derive(Clone, Copy, PartialEq, Debug, finite_repr_derive::Finite)]
+    enum Local {
+      A,
+      B(bool),
+    }
+
+    assert_eq!(<Local as FiniteRepr>::REPRS, 3);
+    assert_eq!(Local::B(true).into_finite::<u8>(), Some(2));
+    assert_eq!(Local::from_finite(2u8), Some(Local::B(true)));
+  }
+
+  #[test]
+  fn test_wider_unsigned_impls() {
+    assert_eq!(u16::MAX.into_finite::<u32>(), Some(65535));
+    assert_eq!(u16::MAX.into_finite::<u8>(), None);
+    assert_eq!(u16::from_finite(65535u32), Some(u16::MAX));
+    assert_eq!(u16::from_finite(65536u32), None);
+  }
+
+  #[test]
+  #[cfg(target_pointer_width = "64")]
+  fn test_u32_impls() {
+    assert_eq!(u32::MAX.into_finite::<u64>(), Some(u32::MAX as u64));
+    assert_eq!(u32::from_finite(1u64 << 32), None);
+  }
+
   #[test]
   fn test_option_impls() {
     for item in [None, Some(u8::MIN), Some(u8::MAX)].iter() {
@@ -152,6 +564,223 @@ mod tests {
     }
   }
 
+  #[test]
+  fn test_option_encodes_none_last() {
+    assert_eq!(None::<bool>.into_finite::<u8>(), Some(2));
+    assert_eq!(Option::<bool>::from_finite(2u8), Some(None));
+    assert_eq!(Option::<bool>::from_finite(3u8), None);
+  }
+
+  #[test]
+  fn test_result_encodes_err_after_ok() {
+    type ResultT = Result<bool, bool>;
+
+    for (item, number) in [
+      (Ok(false), 0),
+      (Ok(true), 1),
+      (Err(false), 2),
+      (Err(true), 3),
+    ] {
+      assert_eq!(item.into_finite::<u8>(), Some(number));
+      assert_eq!(ResultT::from_finite(number), Some(item));
+    }
+
+    assert_eq!(ResultT::from_finite(4u8), None);
+  }
+
+  #[test]
+  fn test_signed_values() {
+    type TupleT = (bool, Option<bool>);
+
+    for item in [(false, None), (true, Some(false)), (false, Some(true))].iter() {
+      let unsigned = item.into_finite::<u8>();
+      let signed = item.into_finite::<i8>().map(|n| n as u8);
+      assert_eq!(unsigned, signed);
+      assert_eq!(
+        Some(*item),
+        item.into_finite::<i16>().and_then(TupleT::from_finite)
+      );
+    }
+
+    assert_eq!(false.into_finite::<i8>(), Some(0));
+    assert_eq!(bool::from_finite(i8::MIN), None);
+    assert_eq!(u8::from_finite(-1i32), None);
+    assert_eq!(u8::MAX.into_finite::<i8>(), None);
+  }
+
+  #[test]
+  fn test_pointer_sized_values() {
+    for item in [(u8::MIN, false), (u8::MAX, true)].iter() {
+      let encoded = item.into_finite::<usize>();
+      assert_eq!(encoded, item.into_finite::<isize>().map(|n| n as usize));
+      assert_eq!(Some(*item), encoded.and_then(<(u8, bool)>::from_finite));
+    }
+
+    assert_eq!(bool::from_finite(-1isize), None);
+  }
+
+  #[test]
+  fn test_non_zero_targets() {
+    use std::num::{NonZeroU32, NonZeroU8};
+
+    let first = (u8::MIN, false).into_finite_target::<NonZeroU32>();
+    assert_eq!(first, NonZeroU32::new(1));
+    assert_eq!(
+      first.and_then(<(u8, bool)>::from_finite_target),
+      Some((u8::MIN, false))
+    );
+
+    for item in [None, Some(false), Some(true)].iter() {
+      let encoded = item.into_finite_target::<NonZeroU8>();
+      assert_eq!(
+        Some(*item),
+        encoded.and_then(Option::<bool>::from_finite_target)
+      );
+    }
+
+    // The largest number has nothing to be offset to.
+    assert_eq!(u8::MAX.into_finite_target::<NonZeroU8>(), None);
+    assert_eq!(u8::MAX.into_finite_target::<u8>(), Some(u8::MAX));
+    assert_eq!(std::mem::size_of::<Option<NonZeroU32>>(), 4);
+  }
+
+  #[test]
+  fn test_byte_targets() {
+    use crate::{bytes_required, BigEndian, LittleEndian};
+
+    type TupleT = (u8, (bool, bool));
+    const BYTES: usize = bytes_required(TupleT::REPRS);
+    assert_eq!(BYTES, 2);
+
+    let item: TupleT = (0x34, (false, true));
+    let big = item.into_finite_target::<BigEndian<BYTES>>();
+    let little = item.into_finite_target::<LittleEndian<BYTES>>();
+    assert_eq!(big, Some(BigEndian([0x02, 0x34])));
+    assert_eq!(little, Some(LittleEndian([0x34, 0x02])));
+    assert_eq!(big.and_then(TupleT::from_finite_target), Some(item));
+    assert_eq!(little.and_then(TupleT::from_finite_target), Some(item));
+
+    // Too few bytes to hold the number, or a number too large
+    // to be a representation of the type.
+    assert_eq!(item.into_finite_target::<BigEndian<1>>(), None);
+    assert_eq!(TupleT::from_finite_target(BigEndian([0x04, 0x00])), None);
+    assert_eq!(TupleT::from_finite_target(LittleEndian([0xFF; 16])), None);
+
+    assert_eq!(bytes_required(0), 0);
+    assert_eq!(bytes_required(256), 1);
+    assert_eq!(bytes_required(257), 2);
+  }
+
+  #[test]
+  fn test_js_safe_int_target() {
+    use crate::JsSafeInt;
+
+    type TupleT = (u8, u8, bool);
+
+    for item in [(0, 0, false), (u8::MAX, 7, true)].iter() {
+      let encoded = item.into_finite_target::<JsSafeInt>().unwrap();
+      assert_eq!(encoded.0.fract(), 0.0);
+      assert_eq!(Some(*item), TupleT::from_finite_target(encoded));
+    }
+
+    assert_eq!(TupleT::from_finite_target(JsSafeInt(1.5)), None);
+    assert_eq!(TupleT::from_finite_target(JsSafeInt(-1.0)), None);
+    assert_eq!(TupleT::from_finite_target(JsSafeInt(f64::NAN)), None);
+    assert_eq!(u8::from_finite_target(JsSafeInt(2f64.powi(53))), None);
+    assert_eq!(JsSafeInt::MAX as f64 + 1.0, 2f64.powi(53));
+  }
+
+  #[test]
+  fn test_overflow_fails() {
+    use crate::Lexicographic;
+
+    // Each of these has a number larger than the target.
+    assert_eq!((u8::MAX, u8::MAX, true).into_finite::<u16>(), None);
+    assert_eq!((u8::MAX, true).into_finite::<u8>(), None);
+    assert_eq!(Err::<bool, u8>(u8::MAX).into_finite::<u8>(), None);
+    assert_eq!(Lexicographic((true, u8::MAX)).into_finite::<u8>(), None);
+    assert_eq!((u8::MAX, u8::MAX, true).into_finite::<i16>(), None);
+    assert_eq!(u8::MAX.inc(), None);
+    assert_eq!(u8::MIN.dec(), None);
+
+    // The largest place value doesn't fit, so only the last
+    // element of numbers smaller than it can be decoded.
+    assert_eq!(
+      <(u8, u8, bool)>::from_finite(u16::MAX),
+      Some((255, 255, false))
+    );
+  }
+
+  #[test]
+  fn test_overflow_policies() {
+    use crate::Overflow;
+
+    let item = (u8::MAX, true, Some(false));
+    let exact = item.into_finite::<u32>().unwrap();
+    assert_eq!(item.into_finite_with::<u8>(Overflow::Checked), None);
+    assert_eq!(
+      item.into_finite_with::<u8>(Overflow::Saturating),
+      Some(u8::MAX)
+    );
+    assert_eq!(
+      item.into_finite_with::<u8>(Overflow::Wrapping),
+      Some(exact as u8)
+    );
+    assert_eq!(
+      item.into_finite_with::<i8>(Overflow::Wrapping),
+      Some((exact % 128) as i8)
+    );
+
+    // Numbers that fit are the same with every policy.
+    for overflow in [Overflow::Checked, Overflow::Saturating, Overflow::Wrapping] {
+      assert_eq!(Err::<bool, u8>(7).into_finite_with::<u8>(overflow), Some(9));
+      assert_eq!(item.into_finite_with::<u32>(overflow), Some(exact));
+    }
+  }
+
+  #[test]
+  fn test_lenient_decoding() {
+    use crate::Overflow;
+
+    type OptionT = Option<bool>;
+
+    assert_eq!(OptionT::from_finite_with(3u8, Overflow::Checked), None);
+    assert_eq!(
+      OptionT::from_finite_with(3u8, Overflow::Saturating),
+      Some(None)
+    );
+    assert_eq!(
+      OptionT::from_finite_with(4u8, Overflow::Wrapping),
+      Some(Some(true))
+    );
+    assert_eq!(OptionT::from_finite_with(-1i8, Overflow::Wrapping), None);
+    assert_eq!(
+      u8::from_finite_with(u8::MAX, Overflow::Checked),
+      Some(u8::MAX)
+    );
+    assert_eq!(u8::from_finite_with(300u16, Overflow::Wrapping), Some(44));
+    assert_eq!(
+      u8::from_finite_with(300u16, Overflow::Saturating),
+      Some(u8::MAX)
+    );
+
+    // Numbers that are in range decode the same with every policy.
+    for overflow in [Overflow::Checked, Overflow::Saturating, Overflow::Wrapping] {
+      assert_eq!(OptionT::from_finite_with(1u8, overflow), Some(Some(true)));
+    }
+  }
+
+  #[test]
+  fn test_decode_errors() {
+    type OptionT = Option<bool>;
+
+    assert_eq!(OptionT::try_from_finite(2u8), Ok(None));
+    assert_eq!(OptionT::try_from_finite(3u8), Err(DecodeError::OutOfRange));
+    assert_eq!(OptionT::try_from_finite(-1i8), Err(DecodeError::OutOfRange));
+    assert_eq!(u8::try_from_finite(300u16), Err(DecodeError::OutOfRange));
+    assert_eq!(u8::try_from_finite(200u16), Ok(200));
+  }
+
   #[test]
   fn test_result_impls() {
     type ResultT = Result<u8, u8>;
@@ -163,6 +792,31 @@ mod tests {
     }
   }
 
+  #[test]
+  fn test_finite_cmp_matches_encoding() {
+    use crate::Lexicographic;
+
+    fn assert_matches<T: FiniteEncoding + FiniteDecoding>() {
+      let values: Vec<T> = (0..T::REPRS).filter_map(T::from_finite).collect();
+      for a in values.iter() {
+        for b in values.iter() {
+          let a_number = a.into_finite::<usize>();
+          let b_number = b.into_finite::<usize>();
+          assert_eq!(a.finite_cmp(b), a_number.cmp(&b_number));
+        }
+      }
+    }
+
+    assert_matches::<bool>();
+    assert_matches::<u8>();
+    assert_matches::<Option<bool>>();
+    assert_matches::<(bool, Option<bool>)>();
+    assert_matches::<(Option<bool>, bool, bool)>();
+    assert_matches::<Result<bool, Option<bool>>>();
+    assert_matches::<Lexicographic<(Option<bool>, bool)>>();
+    assert_matches::<Lexicographic<(bool, Option<bool>, bool)>>();
+  }
+
   #[test]
   fn test_tuple_impls() {
     type TupleT = (u8, bool);
@@ -181,6 +835,40 @@ mod tests {
     }
   }
 
+  #[test]
+  fn test_radix_larger_than_target() {
+    use crate::Lexicographic;
+
+    // The encodings fit in a `u8` even though a radix doesn't.
+    assert_eq!(Some(5u8).into_finite::<u8>(), Some(5));
+    assert_eq!(Option::<u8>::from_finite(5u8), Some(Some(5)));
+    assert_eq!(None::<u8>.into_finite::<u8>(), None);
+
+    type ResultT = Result<u8, bool>;
+    assert_eq!(ResultT::Ok(200).into_finite::<u8>(), Some(200));
+    assert_eq!(ResultT::from_finite(200u8), Some(Ok(200)));
+    assert_eq!(ResultT::Err(false).into_finite::<u8>(), None);
+
+    assert_eq!((5u8, false).into_finite::<u8>(), Some(5));
+    assert_eq!(<(u8, bool)>::from_finite(5u8), Some((5, false)));
+    assert_eq!((5u8, true).into_finite::<u8>(), None);
+
+    let item = (255u8, 255u8, false);
+    assert_eq!(item.into_finite::<u16>(), Some(65535));
+    assert_eq!(<(u8, u8, bool)>::from_finite(65535u16), Some(item));
+
+    let item = Lexicographic((false, 255u8));
+    assert_eq!(item.into_finite::<u8>(), Some(255));
+    assert_eq!(Lexicographic::<(bool, u8)>::from_finite(255u8), Some(item));
+
+    let item = Lexicographic((false, false, 255u8));
+    assert_eq!(item.into_finite::<u8>(), Some(255));
+    assert_eq!(
+      Lexicographic::<(bool, bool, u8)>::from_finite(255u8),
+      Some(item)
+    );
+  }
+
   #[test]
   fn test_tuple_tuple_impls() {
     type TupleTupleA = (u8, bool, bool);
@@ -221,4 +909,74 @@ mod tests {
       assert_eq!(Some(*item), decoded);
     }
   }
+
+  #[test]
+  fn test_lexicographic_impls() {
+    use crate::Lexicographic;
+
+    let items = [
+      Lexicographic((false, u8::MIN, false)),
+      Lexicographic((false, u8::MIN, true)),
+      Lexicographic((false, u8::MAX, false)),
+      Lexicographic((true, u8::MIN, false)),
+      Lexicographic((true, u8::MAX, true)),
+    ];
+
+    let encoded: Vec<_> = items.iter().map(|item| item.into_finite::<u16>()).collect();
+    let decoded: Vec<_> = encoded
+      .iter()
+      .map(|n| n.and_then(Lexicographic::from_finite))
+      .collect();
+    assert_eq!(decoded, items.iter().copied().map(Some).collect::<Vec<_>>());
+
+    // Sorted values are encoded as sorted numbers.
+    assert!(encoded.windows(2).all(|pair| pair[0] < pair[1]));
+
+    for item in [
+      Lexicographic((u8::MIN, true)),
+      Lexicographic((u8::MAX, false)),
+    ]
+    .iter()
+    {
+      let encoded = item.into_finite::<u16>();
+      assert_eq!(Some(*item), encoded.and_then(Lexicographic::from_finite));
+    }
+  }
+
+  #[test]
+  fn test_compact_impls() {
+    use crate::{FiniteCompact, Smallest};
+
+    assert_eq!(<(u8, bool)>::BITS_REQUIRED, 9);
+    assert_eq!(<(u8, bool)>::BYTES_REQUIRED, 2);
+    assert_eq!(<Option<u8>>::BYTES_REQUIRED, 2);
+    assert_eq!(bool::BYTES_REQUIRED, 1);
+
+    let compact: Smallest<bool> = true.into_compact().unwrap();
+    assert_eq!(bool::from_compact(compact), Some(true));
+    assert_eq!(
+      u8::MAX.into_compact().and_then(u8::from_compact),
+      Some(u8::MAX)
+    );
+  }
+
+  #[test]
+  fn test_bits_required() {
+    use crate::bits_required;
+
+    for (reprs, bits) in [
+      (0, 0),
+      (1, 0),
+      (2, 1),
+      (3, 2),
+      (4, 2),
+      (5, 3),
+      (256, 8),
+      (257, 9),
+    ] {
+      assert_eq!(bits_required(reprs), bits);
+    }
+
+    assert_eq!(bits_required(usize::MAX), usize::BITS);
+  }
 }
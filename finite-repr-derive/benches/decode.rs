@@ -0,0 +1,231 @@
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use finite_repr::{FiniteDecoding, FiniteRepr};
+
+/**
+ * Each variant holds a `bool` so none of them can be decoded
+ * with a cast, the cost of decoding a variant shouldn't depend
+ * on its position in the enum.
+ */
+#[derive(FiniteRepr, FiniteDecoding)]
+#[allow(dead_code)]
+enum Wide {
+  V000(bool),
+  V001(bool),
+  V002(bool),
+  V003(bool),
+  V004(bool),
+  V005(bool),
+  V006(bool),
+  V007(bool),
+  V008(bool),
+  V009(bool),
+  V010(bool),
+  V011(bool),
+  V012(bool),
+  V013(bool),
+  V014(bool),
+  V015(bool),
+  V016(bool),
+  V017(bool),
+  V018(bool),
+  V019(bool),
+  V020(bool),
+  V021(bool),
+  V022(bool),
+  V023(bool),
+  V024(bool),
+  V025(bool),
+  V026(bool),
+  V027(bool),
+  V028(bool),
+  V029(bool),
+  V030(bool),
+  V031(bool),
+  V032(bool),
+  V033(bool),
+  V034(bool),
+  V035(bool),
+  V036(bool),
+  V037(bool),
+  V038(bool),
+  V039(bool),
+  V040(bool),
+  V041(bool),
+  V042(bool),
+  V043(bool),
+  V044(bool),
+  V045(bool),
+  V046(bool),
+  V047(bool),
+  V048(bool),
+  V049(bool),
+  V050(bool),
+  V051(bool),
+  V052(bool),
+  V053(bool),
+  V054(bool),
+  V055(bool),
+  V056(bool),
+  V057(bool),
+  V058(bool),
+  V059(bool),
+  V060(bool),
+  V061(bool),
+  V062(bool),
+  V063(bool),
+  V064(bool),
+  V065(bool),
+  V066(bool),
+  V067(bool),
+  V068(bool),
+  V069(bool),
+  V070(bool),
+  V071(bool),
+  V072(bool),
+  V073(bool),
+  V074(bool),
+  V075(bool),
+  V076(bool),
+  V077(bool),
+  V078(bool),
+  V079(bool),
+  V080(bool),
+  V081(bool),
+  V082(bool),
+  V083(bool),
+  V084(bool),
+  V085(bool),
+  V086(bool),
+  V087(bool),
+  V088(bool),
+  V089(bool),
+  V090(bool),
+  V091(bool),
+  V092(bool),
+  V093(bool),
+  V094(bool),
+  V095(bool),
+  V096(bool),
+  V097(bool),
+  V098(bool),
+  V099(bool),
+  V100(bool),
+  V101(bool),
+  V102(bool),
+  V103(bool),
+  V104(bool),
+  V105(bool),
+  V106(bool),
+  V107(bool),
+  V108(bool),
+  V109(bool),
+  V110(bool),
+  V111(bool),
+  V112(bool),
+  V113(bool),
+  V114(bool),
+  V115(bool),
+  V116(bool),
+  V117(bool),
+  V118(bool),
+  V119(bool),
+  V120(bool),
+  V121(bool),
+  V122(bool),
+  V123(bool),
+  V124(bool),
+  V125(bool),
+  V126(bool),
+  V127(bool),
+  V128(bool),
+  V129(bool),
+  V130(bool),
+  V131(bool),
+  V132(bool),
+  V133(bool),
+  V134(bool),
+  V135(bool),
+  V136(bool),
+  V137(bool),
+  V138(bool),
+  V139(bool),
+  V140(bool),
+  V141(bool),
+  V142(bool),
+  V143(bool),
+  V144(bool),
+  V145(bool),
+  V146(bool),
+  V147(bool),
+  V148(bool),
+  V149(bool),
+  V150(bool),
+  V151(bool),
+  V152(bool),
+  V153(bool),
+  V154(bool),
+  V155(bool),
+  V156(bool),
+  V157(bool),
+  V158(bool),
+  V159(bool),
+  V160(bool),
+  V161(bool),
+  V162(bool),
+  V163(bool),
+  V164(bool),
+  V165(bool),
+  V166(bool),
+  V167(bool),
+  V168(bool),
+  V169(bool),
+  V170(bool),
+  V171(bool),
+  V172(bool),
+  V173(bool),
+  V174(bool),
+  V175(bool),
+  V176(bool),
+  V177(bool),
+  V178(bool),
+  V179(bool),
+  V180(bool),
+  V181(bool),
+  V182(bool),
+  V183(bool),
+  V184(bool),
+  V185(bool),
+  V186(bool),
+  V187(bool),
+  V188(bool),
+  V189(bool),
+  V190(bool),
+  V191(bool),
+  V192(bool),
+  V193(bool),
+  V194(bool),
+  V195(bool),
+  V196(bool),
+  V197(bool),
+  V198(bool),
+  V199(bool),
+}
+
+fn decode_by_position(c: &mut Criterion) {
+  let mut group = c.benchmark_group("decode 200 variants");
+
+  for position in [0usize, 50, 100, 150, 199] {
+    let number = (position * 2) as u16;
+    group.bench_with_input(
+      BenchmarkId::from_parameter(position),
+      &number,
+      |b, number| b.iter(|| Wide::from_finite(black_box(*number))),
+    );
+  }
+
+  group.finish();
+  assert_eq!(Wide::REPRS, 400);
+}
+
+criterion_group!(benches, decode_by_position);
+criterion_main!(benches);
@@ -0,0 +1,409 @@
+use crate::codegen::field_codegen::FieldOrder;
+use proc_macro2::{Span, TokenStream, TokenTree};
+use syn::ext::IdentExt;
+use syn::parse::{Parse, ParseStream};
+use syn::punctuated::Punctuated;
+use syn::spanned::Spanned;
+use syn::{Attribute, Ident, LitInt, LitStr, Path, Token, Type};
+
+pub use self::error::*;
+
+type AttributeResult<T> = Result<T, AttributeError>;
+
+/**
+ * Options set on the type being derived, via the
+ * `#[finite(...)]` attribute.
+ */
+pub struct ContainerAttributes {
+  /**
+   * Set with `#[finite(crate = "path::to::finite_repr")]`, for
+   * when `finite_repr` is re-exported from another crate.
+   */
+  pub crate_path: Option<Path>,
+  /**
+   * Set with `#[finite(tag = u8)]` on a union, the type of
+   * the tag that identifies which arm is initialised.
+   */
+  pub tag: Option<MetaValue>,
+  /**
+   * Set with `#[finite(discriminant)]` on a fieldless enum, to
+   * encode each variant as its discriminant.
+   */
+  pub discriminant: Option<Span>,
+  /**
+   * Set with `#[finite(table)]`, to generate a table of every
+   * value which is used to decode them.
+   */
+  pub table: Option<Span>,
+  /**
+   * Set with `#[finite(bitpacked)]` on a struct, to give each
+   * field a whole number of bits rather than encoding densely.
+   */
+  pub bitpacked: Option<Span>,
+  /**
+   * Set with `#[finite(order = "lexicographic")]`, to make
+   * earlier fields more significant than later ones.
+   */
+  pub order: Option<(Span, FieldOrder)>,
+  /**
+   * Set with `#[finite(fits = "u16")]`, to fail compilation when
+   * the type has more representations than the target can hold.
+   */
+  pub fits: Option<(Span, Type)>,
+  /**
+   * Set with `#[finite(reserve = 16)]`, to fix the number of
+   * representations so values can be added without changing it.
+   */
+  pub reserve: Option<(Span, usize)>,
+}
+
+impl ContainerAttributes {
+  pub fn from_attributes(attributes: &[Attribute]) -> AttributeResult<Self> {
+    let mut crate_path = None;
+    let mut tag = None;
+    let mut discriminant = None;
+    let mut table = None;
+    let mut bitpacked = None;
+    let mut order = None;
+    let mut fits = None;
+    let mut reserve = None;
+
+    for meta in finite_metas(attributes)? {
+      if meta.key == "crate" {
+        let value = meta.value_or_error(InvalidValue::CratePath)?;
+        set_once(&mut crate_path, &meta, parse_path(value)?)?;
+      } else if meta.key == "tag" {
+        let value = meta.value_or_error(InvalidValue::TagType)?;
+        set_once(&mut tag, &meta, value.clone())?;
+      } else if meta.key == "discriminant" {
+        meta.flag_or_error()?;
+        set_once(&mut discriminant, &meta, meta.key.span())?;
+      } else if meta.key == "table" {
+        meta.flag_or_error()?;
+        set_once(&mut table, &meta, meta.key.span())?;
+      } else if meta.key == "bitpacked" {
+        meta.flag_or_error()?;
+        set_once(&mut bitpacked, &meta, meta.key.span())?;
+      } else if meta.key == "order" {
+        let value = meta.value_or_error(InvalidValue::Order)?;
+        set_once(&mut order, &meta, (meta.key.span(), parse_order(value)?))?;
+      } else if meta.key == "fits" {
+        let value = meta.value_or_error(InvalidValue::Fits)?;
+        set_once(&mut fits, &meta, (meta.key.span(), parse_fits(value)?))?;
+      } else if meta.key == "reserve" {
+        let value = meta.value_or_error(InvalidValue::Reserve)?;
+        set_once(
+          &mut reserve,
+          &meta,
+          (meta.key.span(), parse_reserve(value)?),
+        )?;
+      } else {
+        return Err(AttributeError::UnknownAttribute(meta.key.span()));
+      }
+    }
+
+    Ok(ContainerAttributes {
+      crate_path,
+      tag,
+      discriminant,
+      table,
+      bitpacked,
+      order,
+      fits,
+      reserve,
+    })
+  }
+}
+
+/**
+ * Options set on a field, via the `#[finite(...)]` attribute.
+ */
+pub struct FieldAttributes {
+  /**
+   * On a union arm the tag value identifying the arm, as in
+   * `#[finite(tag = 0)]`. On the field of a struct or variant
+   * holding a union, the sibling field storing its tag, as in
+   * `#[finite(tag = kind)]`.
+   */
+  pub tag: Option<MetaValue>,
+}
+
+impl FieldAttributes {
+  pub fn from_attributes(attributes: &[Attribute]) -> AttributeResult<Self> {
+    let mut tag = None;
+
+    for meta in finite_metas(attributes)? {
+      if meta.key == "tag" {
+        let value = meta.value_or_error(InvalidValue::Tag)?;
+        set_once(&mut tag, &meta, value.clone())?;
+      } else {
+        return Err(AttributeError::UnknownAttribute(meta.key.span()));
+      }
+    }
+
+    Ok(FieldAttributes { tag })
+  }
+}
+
+/**
+ * Options set on an enum variant, via the `#[finite(...)]`
+ * attribute. Options meant for other positions are reported
+ * rather than silently ignored.
+ */
+pub struct VariantAttributes {
+  /**
+   * Set with `#[finite(fallback)]` on a fieldless variant, the
+   * value decoded from numbers that don't decode otherwise.
+   */
+  pub fallback: Option<Span>,
+  /**
+   * Set with `#[finite(other)]` on the last variant, which has a
+   * single field holding any number beyond the other variants.
+   */
+  pub other: Option<Span>,
+}
+
+impl VariantAttributes {
+  pub fn from_attributes(attributes: &[Attribute]) -> AttributeResult<Self> {
+    let mut fallback = None;
+    let mut other = None;
+
+    for meta in finite_metas(attributes)? {
+      if meta.key == "fallback" {
+        meta.flag_or_error()?;
+        set_once(&mut fallback, &meta, meta.key.span())?;
+      } else if meta.key == "other" {
+        meta.flag_or_error()?;
+        set_once(&mut other, &meta, meta.key.span())?;
+      } else {
+        return Err(AttributeError::UnsupportedPosition(
+          meta.key.span(),
+          meta.key.to_string(),
+          "variant",
+        ));
+      }
+    }
+
+    Ok(VariantAttributes { fallback, other })
+  }
+}
+
+/**
+ * The tokens on the right hand side of `key = ...`, these are
+ * kept unparsed as what they mean depends on where the attribute
+ * is, `tag = u8` is a type on a union but `tag = 0` is a value on
+ * one of its arms.
+ */
+#[derive(Clone)]
+pub struct MetaValue {
+  pub span: Span,
+  pub tokens: TokenStream,
+}
+
+impl MetaValue {
+  pub fn parse<T: Parse>(&self, expected: InvalidValue) -> AttributeResult<T> {
+    syn::parse2(self.tokens.clone()).map_err(|_| AttributeError::InvalidValue(self.span, expected))
+  }
+}
+
+/**
+ * A single item of `#[finite(a, b = c)]`, either a bare key or
+ * a key with a value.
+ */
+struct FiniteMeta {
+  key: Ident,
+  value: Option<MetaValue>,
+}
+
+impl FiniteMeta {
+  fn value_or_error(&self, expected: InvalidValue) -> AttributeResult<&MetaValue> {
+    self
+      .value
+      .as_ref()
+      .ok_or_else(|| AttributeError::InvalidValue(self.key.span(), expected))
+  }
+
+  fn flag_or_error(&self) -> AttributeResult<()> {
+    match &self.value {
+      Some(value) => Err(AttributeError::UnexpectedValue(
+        value.span,
+        self.key.to_string(),
+      )),
+      None => Ok(()),
+    }
+  }
+}
+
+impl Parse for FiniteMeta {
+  fn parse(input: ParseStream) -> syn::Result<Self> {
+    // `parse_any` so keywords like `crate` can be used as keys.
+    let key = input.call(Ident::parse_any)?;
+
+    if !input.peek(Token![=]) {
+      return Ok(FiniteMeta { key, value: None });
+    }
+
+    let eq_token: Token![=] = input.parse()?;
+    let mut tokens = TokenStream::new();
+
+    while !input.is_empty() && !input.peek(Token![,]) {
+      let token: TokenTree = input.parse()?;
+      tokens.extend(Some(token));
+    }
+
+    if tokens.is_empty() {
+      return Err(syn::Error::new(eq_token.span, "expected a value"));
+    }
+
+    let span = tokens.span();
+    Ok(FiniteMeta {
+      key,
+      value: Some(MetaValue { span, tokens }),
+    })
+  }
+}
+
+/**
+ * Flattens every `#[finite(a, b = c)]` attribute into its
+ * nested items, ignoring attributes for other macros.
+ */
+fn finite_metas(attributes: &[Attribute]) -> AttributeResult<Vec<FiniteMeta>> {
+  let mut metas = vec![];
+
+  for attribute in attributes.iter() {
+    if !attribute.path.is_ident("finite") {
+      continue;
+    }
+
+    let list = attribute
+      .parse_args_with(Punctuated::<FiniteMeta, Token![,]>::parse_terminated)
+      .map_err(|_| AttributeError::MalformedAttribute(attribute.span()))?;
+
+    metas.extend(list);
+  }
+
+  Ok(metas)
+}
+
+fn set_once<T>(slot: &mut Option<T>, meta: &FiniteMeta, value: T) -> AttributeResult<()> {
+  if slot.is_some() {
+    let name = meta.key.to_string();
+    return Err(AttributeError::DuplicateAttribute(meta.key.span(), name));
+  }
+
+  *slot = Some(value);
+  Ok(())
+}
+
+fn parse_path(value: &MetaValue) -> AttributeResult<Path> {
+  value
+    .parse::<LitStr>(InvalidValue::CratePath)?
+    .parse()
+    .map_err(|_| AttributeError::InvalidValue(value.span, InvalidValue::CratePath))
+}
+
+fn parse_order(value: &MetaValue) -> AttributeResult<FieldOrder> {
+  match value.parse::<LitStr>(InvalidValue::Order)?.value().as_str() {
+    "lexicographic" => Ok(FieldOrder::Lexicographic),
+    _ => Err(AttributeError::InvalidValue(
+      value.span,
+      InvalidValue::Order,
+    )),
+  }
+}
+
+fn parse_fits(value: &MetaValue) -> AttributeResult<Type> {
+  value
+    .parse::<LitStr>(InvalidValue::Fits)?
+    .parse()
+    .map_err(|_| AttributeError::InvalidValue(value.span, InvalidValue::Fits))
+}
+
+fn parse_reserve(value: &MetaValue) -> AttributeResult<usize> {
+  value
+    .parse::<LitInt>(InvalidValue::Reserve)?
+    .base10_parse()
+    .map_err(|_| AttributeError::InvalidValue(value.span, InvalidValue::Reserve))
+}
+
+mod error {
+  use crate::impl_error::CompilerError;
+
+  /**
+   * What was expected of an option's value.
+   */
+  #[derive(Clone, Copy)]
+  pub enum InvalidValue {
+    CratePath,
+    TagType,
+    Tag,
+    TagField,
+    Order,
+    Fits,
+    Reserve,
+  }
+
+  pub enum AttributeError {
+    MalformedAttribute(proc_macro2::Span),
+    UnknownAttribute(proc_macro2::Span),
+    DuplicateAttribute(proc_macro2::Span, String),
+    UnsupportedPosition(proc_macro2::Span, String, &'static str),
+    InvalidValue(proc_macro2::Span, InvalidValue),
+    UnexpectedValue(proc_macro2::Span, String),
+  }
+
+  impl CompilerError for AttributeError {
+    fn compile_error(&self) -> proc_macro2::TokenStream {
+      match *self {
+        AttributeError::DuplicateAttribute(span, ref name) => {
+          let message = format!("`{}` is set more than once", name);
+          quote::quote_spanned! {
+            span => compile_error!(#message);
+          }
+        }
+        AttributeError::UnsupportedPosition(span, ref name, position) => {
+          let message = format!("`{}` isn't supported on a {}", name, position);
+          quote::quote_spanned! {
+            span => compile_error!(#message);
+          }
+        }
+        AttributeError::UnexpectedValue(span, ref name) => {
+          let message = format!("`{}` doesn't take a value", name);
+          quote::quote_spanned! {
+            span => compile_error!(#message);
+          }
+        }
+        AttributeError::MalformedAttribute(span) => {
+          quote::quote_spanned! {
+            span => compile_error!("expected an attribute of the form `#[finite(...)]`");
+          }
+        }
+        AttributeError::UnknownAttribute(span) => {
+          quote::quote_spanned! {
+            span => compile_error!("unknown `finite` attribute");
+          }
+        }
+        AttributeError::InvalidValue(span, expected) => {
+          let message = match expected {
+            InvalidValue::CratePath => {
+              "expected a path, as in `#[finite(crate = \"path::to::finite_repr\")]`"
+            }
+            InvalidValue::TagType => "expected the type of the tag, as in `#[finite(tag = u8)]`",
+            InvalidValue::Tag => "expected a tag, as in `#[finite(tag = 0)]`",
+            InvalidValue::TagField => {
+              "expected the field holding the tag, as in `#[finite(tag = kind)]`"
+            }
+            InvalidValue::Order => "expected `#[finite(order = \"lexicographic\")]`",
+            InvalidValue::Fits => "expected a target type, as in `#[finite(fits = \"u16\")]`",
+            InvalidValue::Reserve => {
+              "expected a number of representations, as in `#[finite(reserve = 16)]`"
+            }
+          };
+          quote::quote_spanned! {
+            span => compile_error!(#message);
+          }
+        }
+      }
+    }
+  }
+}
@@ -0,0 +1,194 @@
+use super::enum_codegen::sum;
+use super::field_codegen::{
+  check_field, decode_field, encode_field, field_error_stmts, reprs_of, FieldCodegen, FieldInfo,
+};
+use super::idents;
+use proc_macro2::{Ident, TokenStream};
+use quote::quote;
+
+/**
+ * The number of bits occupied by the encoded field at `digit`,
+ * enough for every one of its representations.
+ */
+fn bits_of(codegen: &FieldCodegen, digit: usize, krate: &TokenStream) -> TokenStream {
+  let (_, field) = codegen.encoded()[digit];
+  let reprs = reprs_of(&field.ty, krate);
+  quote! { #krate::bits_required(#reprs) }
+}
+
+/**
+ * The number of bits occupied by every encoded field before
+ * `digit`, which is how far that field is shifted.
+ */
+fn offset_of(codegen: &FieldCodegen, digit: usize, krate: &TokenStream) -> TokenStream {
+  let offset = sum((0..digit).map(|d| bits_of(codegen, d, krate)));
+  quote! { (#offset) }
+}
+
+/**
+ * The number of bits occupied by every encoded field.
+ */
+fn bits_expression(codegen: &FieldCodegen, krate: &TokenStream) -> TokenStream {
+  offset_of(codegen, codegen.encoded().len(), krate)
+}
+
+/**
+ * Every combination of bits is counted as a representation,
+ * including those that fail to decode.
+ */
+pub fn size_expression(codegen: &FieldCodegen, krate: &TokenStream) -> TokenStream {
+  match codegen.encoded().as_slice() {
+    [] => codegen.calc_size(krate),
+    _ => {
+      let bits = bits_expression(codegen, krate);
+      quote! { (1usize << #bits) }
+    }
+  }
+}
+
+/**
+ * How many more bits the packed layout uses than the densest
+ * encoding of the same fields.
+ */
+pub fn wasted_bits_expression(codegen: &FieldCodegen, krate: &TokenStream) -> TokenStream {
+  let bits = bits_expression(codegen, krate);
+  let dense = codegen.calc_size(krate);
+  quote! { #bits - #krate::bits_required(#dense) }
+}
+
+/**
+ * Encodes the fields bound by `field_destructor`, each field
+ * is shifted past the bits of the fields before it. A field is
+ * only checked to fit in the generic type once shifted when it
+ * isn't zero, the same as the place values of a dense encoding.
+ */
+pub fn branch_size(
+  codegen: &FieldCodegen,
+  generic: &Ident,
+  krate: &TokenStream,
+) -> (TokenStream, TokenStream) {
+  let fields = match codegen.encoded() {
+    fields if fields.is_empty() => return codegen.branch_size(generic, krate),
+    fields => fields,
+  };
+
+  let option = idents::option_path();
+  let (index, field) = fields[0];
+  let mut tokens = encode_field(index, field, generic, krate);
+
+  for (digit, (index, field)) in fields.iter().enumerate().skip(1) {
+    let offset = offset_of(codegen, digit, krate);
+    let number = encode_field(*index, field, generic, krate);
+    let digit_value = idents::local(&format!("digit_{}", digit));
+    let place = quote! { (1usize << #offset) };
+
+    let value = quote! {{
+      let #digit_value = #number;
+      if #digit_value == #generic::ZERO {
+        #generic::ZERO
+      } else if #place > #generic::MAX_USIZE {
+        return #option::None;
+      } else {
+        #krate::FiniteValue::checked_mul(#generic::constant(#place), #digit_value)?
+      }
+    }};
+    tokens = quote! { #tokens | #value };
+  }
+
+  (quote! {}, quote! { ( #tokens ) })
+}
+
+/**
+ * Decodes each field from `value` by masking its bits then
+ * shifting them away, the fields themselves reject any bit
+ * pattern that isn't one of their representations.
+ */
+pub fn branch_construction(
+  codegen: &FieldCodegen,
+  value: &TokenStream,
+  generic_type: &Ident,
+  krate: &TokenStream,
+) -> (TokenStream, TokenStream, Option<TokenStream>) {
+  if codegen.encoded().is_empty() {
+    return codegen.branch_construction(value, generic_type, krate);
+  }
+
+  let stmts = split_bits(
+    codegen,
+    value,
+    generic_type,
+    krate,
+    |index, field, digit| decode_field(index, field, digit, krate),
+  );
+
+  (stmts, codegen.field_destructor(), None)
+}
+
+/**
+ * Returns why `value` doesn't decode, checking the bits of
+ * every field with `check_field`.
+ */
+pub fn branch_error(
+  codegen: &FieldCodegen,
+  value: &TokenStream,
+  generic_type: &Ident,
+  krate: &TokenStream,
+) -> TokenStream {
+  let stmts = split_bits(codegen, value, generic_type, krate, |_, field, digit| {
+    check_field(field, digit, krate)
+  });
+  field_error_stmts(stmts, krate)
+}
+
+/**
+ * Splits `value` into the bits of each field, passing them to
+ * `field_stmts`.
+ */
+fn split_bits(
+  codegen: &FieldCodegen,
+  value: &TokenStream,
+  generic_type: &Ident,
+  krate: &TokenStream,
+  field_stmts: impl Fn(usize, &FieldInfo, &TokenStream) -> TokenStream,
+) -> TokenStream {
+  let fields = codegen.encoded();
+  let rest = idents::local("rest");
+  let mut stmts = quote! {
+    let #rest = #value;
+  };
+
+  for (digit, (index, field)) in fields.iter().enumerate() {
+    let value_passed = if digit == fields.len() - 1 {
+      quote! { #rest }
+    } else {
+      // A mask larger than the generic type covers every bit
+      // of it, leaving all of them for this field.
+      let digit_value = idents::local(&format!("digit_{}", digit));
+      let bits = bits_of(codegen, digit, krate);
+      let mask = quote! { ((1usize << #bits) - 1) };
+      stmts = quote! {
+        #stmts
+        let #digit_value = if #mask > #generic_type::MAX_USIZE {
+          #rest
+        } else {
+          #rest & #generic_type::constant(#mask)
+        };
+        let #rest = if #mask > #generic_type::MAX_USIZE {
+          #generic_type::ZERO
+        } else {
+          #rest >> #bits
+        };
+      };
+      quote! { #digit_value }
+    };
+
+    let field_stmts = field_stmts(*index, field, &value_passed);
+
+    stmts = quote! {
+      #stmts
+      #field_stmts
+    };
+  }
+
+  stmts
+}
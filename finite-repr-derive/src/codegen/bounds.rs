@@ -12,7 +12,7 @@ pub fn add_trait_bounds(generics_in: &Generics, path: &Path) -> Generics {
         path: path.clone(),
       });
 
-      param.bounds.push_value(bound);
+      param.bounds.push(bound);
     }
   }
 
@@ -1,45 +1,196 @@
 use super::field_codegen::FieldCodegen;
+use super::idents;
 use proc_macro2::{Ident, TokenStream};
 use quote::quote;
-use std::collections::HashMap;
-use syn::Variant;
 
-pub struct EnumCodegen(HashMap<Ident, VariantInfo>);
+/**
+ * The variants of an enum in declaration order, which is
+ * also the order in which they're laid out when encoded.
+ */
+pub struct EnumCodegen {
+  variants: Vec<VariantInfo>,
+  primitive: Option<PrimitiveRepr>,
+  /**
+   * Set with `#[finite(other)]` on the last variant, whose field
+   * holds every number beyond the other variants unchanged.
+   */
+  other: bool,
+}
+
+/**
+ * Set when an enum is fieldless with a `#[repr(uN)]` & its
+ * discriminants are contiguous, meaning its in memory layout
+ * is its encoding offset by the first discriminant.
+ */
+pub struct PrimitiveRepr {
+  pub ty: Ident,
+  pub offset: u64,
+}
 
 impl EnumCodegen {
-  pub fn new() -> Self {
-    EnumCodegen(HashMap::new())
+  pub fn new(variants: Vec<VariantInfo>) -> Self {
+    EnumCodegen {
+      variants,
+      primitive: None,
+      other: false,
+    }
+  }
+
+  pub fn with_primitive_repr(variants: Vec<VariantInfo>, primitive: PrimitiveRepr) -> Self {
+    EnumCodegen {
+      variants,
+      primitive: Some(primitive),
+      other: false,
+    }
   }
 
-  pub fn insert_variant(&mut self, variant: &Variant, parent: Option<Ident>) -> &VariantInfo {
-    let repr = FieldCodegen::from_enum_variant(variant);
-    self
-      .0
-      .insert(variant.ident.clone(), VariantInfo { repr, parent });
-    self.0.get(&variant.ident).unwrap()
+  pub fn variants(&self) -> &[VariantInfo] {
+    &self.variants
   }
 
-  pub fn size_expresssion(&self) -> TokenStream {
-    let mut variants = self.0.values();
-    let mut tokens = match variants.next() {
-      None => return quote! { 0 },
-      Some(v) => {
-        let variant_size = v.repr.calc_size();
-        quote! { #variant_size }
+  pub fn primitive_repr(&self) -> Option<&PrimitiveRepr> {
+    self.primitive.as_ref()
+  }
+
+  /**
+   * Marks the last variant as the catch-all for unknown numbers.
+   */
+  pub fn mark_other(&mut self) {
+    self.other = true;
+  }
+
+  /**
+   * Whether the variant at `index` is the catch-all, which is
+   * encoded as the number held by its field.
+   */
+  pub fn is_other(&self, index: usize) -> bool {
+    self.other && index + 1 == self.variants.len()
+  }
+
+  pub fn has_other(&self) -> bool {
+    self.other
+  }
+
+  /**
+   * A catch-all holds the numbers from the floor of its variant
+   * up to the representations of its field, so its field needs
+   * more representations than the other variants or it would
+   * never hold a number.
+   */
+  pub fn size_expression(&self, krate: &TokenStream) -> TokenStream {
+    if !self.other {
+      return sum_sizes(&self.variants, krate);
+    }
+
+    let last = self.variants.len() - 1;
+    let known = idents::local("known");
+    let other = idents::local("other");
+    let known_size = self.floor_expression(last, krate);
+    let other_size = self.variants[last].repr.calc_size(krate);
+    let message = format!(
+      "the field of `{}` needs more representations than the variants before it",
+      self.variants[last].ident
+    );
+    quote! {
+      {
+        let #known = #known_size;
+        let #other = #other_size;
+        if #other <= #known {
+          ::core::panic!(#message);
+        }
+        #other
       }
-    };
+    }
+  }
 
-    for variant in variants {
-      let variant_size = variant.repr.calc_size();
-      tokens = quote! { #tokens + #variant_size };
+  /**
+   * The number of representations of every variant declared
+   * before the variant at `index`, which is also the smallest
+   * number that variant is encoded as.
+   */
+  pub fn floor_expression(&self, index: usize, krate: &TokenStream) -> TokenStream {
+    sum_sizes(&self.variants[..index], krate)
+  }
+
+  /**
+   * Exclusive upper bound of the numbers the variant at
+   * `index` is encoded as.
+   */
+  pub fn ceil_expression(&self, index: usize, krate: &TokenStream) -> TokenStream {
+    sum_sizes(&self.variants[..=index], krate)
+  }
+
+  /**
+   * An array of the floor of each variant followed by the
+   * number of representations in use, which is the size
+   * without any that are reserved.
+   */
+  pub fn offsets_expression(&self, krate: &TokenStream) -> TokenStream {
+    let floors = (0..self.variants.len()).map(|index| sum_sizes(&self.variants[..index], krate));
+    quote! { [ #(#floors,)* <Self as #krate::FiniteRepr>::USED_REPRS ] }
+  }
+
+  /**
+   * The discriminant of the variant at `index`, only valid
+   * when every variant is fieldless.
+   */
+  pub fn discriminant_expression(&self, index: usize) -> TokenStream {
+    let ident = &self.variants[index].ident;
+    quote! { (Self::#ident as usize) }
+  }
+
+  /**
+   * One more than the largest discriminant, so every number up to
+   * & including each discriminant can be represented. Negative
+   * discriminants fail to compile as they have no such number.
+   */
+  pub fn discriminant_span_expression(&self) -> TokenStream {
+    let reprs = idents::local("reprs");
+    let mut tokens = quote! {};
+
+    for (index, variant) in self.variants.iter().enumerate() {
+      let ident = &variant.ident;
+      let discriminant = self.discriminant_expression(index);
+      tokens = quote! {
+        #tokens
+        if (Self::#ident as i128) < 0 {
+          ::core::panic!("`#[finite(discriminant)]` doesn't support negative discriminants");
+        }
+        if #discriminant >= #reprs {
+          #reprs = #discriminant + 1;
+        }
+      };
     }
 
-    tokens
+    quote! {
+      {
+        let mut #reprs = 0;
+        #tokens
+        #reprs
+      }
+    }
   }
 }
 
 pub struct VariantInfo {
-  #[allow(dead_code)]
-  parent: Option<Ident>,
+  pub ident: Ident,
   pub repr: FieldCodegen,
 }
+
+fn sum_sizes(variants: &[VariantInfo], krate: &TokenStream) -> TokenStream {
+  sum(variants.iter().map(|v| v.repr.calc_size(krate)))
+}
+
+pub(super) fn sum<I: IntoIterator<Item = TokenStream>>(sizes: I) -> TokenStream {
+  let mut sizes = sizes.into_iter();
+  let mut tokens = match sizes.next() {
+    None => return quote! { 0 },
+    Some(size) => quote! { #size },
+  };
+
+  for size in sizes {
+    tokens = quote! { #tokens + #size };
+  }
+
+  tokens
+}
@@ -1,6 +1,8 @@
-use proc_macro2::{Ident, Span, TokenStream};
-use quote::quote;
-use syn::{DataStruct, Fields, Type, Variant};
+use super::idents;
+use proc_macro2::{Ident, TokenStream};
+use quote::{quote, quote_spanned};
+use syn::spanned::Spanned;
+use syn::{Fields, Member, Type};
 
 /**
  * Meta data associated with fieldset, along with codegen
@@ -8,252 +10,459 @@ use syn::{DataStruct, Fields, Type, Variant};
  */
 pub enum FieldCodegen {
   Fieldless,
-  Product(Vec<Type>),
-  ProductNamed(Vec<(Ident, Type)>),
+  Product(Vec<FieldInfo>, FieldOrder),
+  ProductNamed(Vec<FieldInfo>, FieldOrder),
+}
+
+/**
+ * Which field is the least significant digit of the product.
+ */
+#[derive(Clone, Copy, PartialEq)]
+pub enum FieldOrder {
+  /**
+   * The first field is the least significant, the default.
+   */
+  LeastSignificantFirst,
+  /**
+   * The first field is the most significant, so numbers are
+   * ordered the same as the values they encode when compared
+   * field by field. Set with `#[finite(order = "lexicographic")]`.
+   */
+  Lexicographic,
+}
+
+pub struct FieldInfo {
+  pub ident: Option<Ident>,
+  pub ty: Type,
+  pub role: FieldRole,
+}
+
+/**
+ * How a field takes part in the encoding.
+ */
+#[derive(Clone, Copy, PartialEq)]
+pub enum FieldRole {
+  /**
+   * Encoded as a digit of the product.
+   */
+  Value,
+  /**
+   * The tag of a union held by another field, this isn't
+   * encoded as the union arm already implies it.
+   */
+  Tag,
+  /**
+   * A union, its arm is identified by the field at this index.
+   */
+  Union { tag: usize },
 }
 
 impl FieldCodegen {
-  pub fn from_struct(struct_data: &DataStruct) -> Self {
-    Self::from_fields(&struct_data.fields)
+  pub fn from_fields(fields: &Fields, order: FieldOrder) -> Self {
+    let infos = fields
+      .iter()
+      .map(|f| FieldInfo {
+        ident: f.ident.clone(),
+        ty: f.ty.clone(),
+        role: FieldRole::Value,
+      })
+      .collect();
+
+    match fields {
+      Fields::Unit => FieldCodegen::Fieldless,
+      Fields::Named(_) => FieldCodegen::ProductNamed(infos, order),
+      Fields::Unnamed(_) => FieldCodegen::Product(infos, order),
+    }
   }
 
-  pub fn from_enum_variant(variant: &Variant) -> Self {
-    Self::from_fields(&variant.fields)
+  pub fn fields(&self) -> &[FieldInfo] {
+    match self {
+      FieldCodegen::Fieldless => &[],
+      FieldCodegen::Product(fields, _) => fields,
+      FieldCodegen::ProductNamed(fields, _) => fields,
+    }
   }
 
-  fn from_fields(fields: &Fields) -> Self {
-    match fields {
-      Fields::Unit => FieldCodegen::Fieldless,
-      Fields::Named(_) => {
-        let tys = fields
-          .iter()
-          .map(|f| {
-            let ident = f.ident.clone().unwrap();
-            (ident, f.ty.clone())
-          })
-          .collect();
-        FieldCodegen::ProductNamed(tys)
+  pub fn order(&self) -> FieldOrder {
+    match self {
+      FieldCodegen::Fieldless => FieldOrder::LeastSignificantFirst,
+      FieldCodegen::Product(_, order) => *order,
+      FieldCodegen::ProductNamed(_, order) => *order,
+    }
+  }
+
+  /**
+   * The index of the field named by `member`.
+   */
+  pub fn position_of(&self, member: &Member) -> Option<usize> {
+    match (self, member) {
+      (FieldCodegen::ProductNamed(fields, _), Member::Named(name)) => {
+        fields.iter().position(|f| f.ident.as_ref() == Some(name))
       }
-      Fields::Unnamed(_) => {
-        let tys = fields.iter().map(|f| f.ty.clone()).collect();
-        FieldCodegen::Product(tys)
+      (FieldCodegen::Product(fields, _), Member::Unnamed(index)) => {
+        let index = index.index as usize;
+        Some(index).filter(|i| *i < fields.len())
       }
+      _ => None,
     }
   }
 
-  pub fn calc_size(&self) -> TokenStream {
-    let types = match self {
-      FieldCodegen::Fieldless => return quote! { 1 },
-      FieldCodegen::Product(types) if types.is_empty() => return quote! { 0 },
-      FieldCodegen::ProductNamed(types) if types.is_empty() => return quote! { 0 },
-      FieldCodegen::Product(types) => types.clone(),
-      FieldCodegen::ProductNamed(types) => types.iter().map(|t| t.1.clone()).collect(),
+  /**
+   * Marks the field at `union` as a union whose arm is identified
+   * by the field at `tag`.
+   */
+  pub fn tag_union(&mut self, union: usize, tag: usize) {
+    let fields = match self {
+      FieldCodegen::Fieldless => return,
+      FieldCodegen::Product(fields, _) => fields,
+      FieldCodegen::ProductNamed(fields, _) => fields,
     };
 
-    let head = types[0].clone();
-    let mut tokens = quote! { (#head::REPRS) };
+    fields[union].role = FieldRole::Union { tag };
+    fields[tag].role = FieldRole::Tag;
+  }
 
-    for field in types[1..].iter() {
-      let seperated = seperate_type(field);
-      tokens = quote! { #tokens * (#seperated::REPRS) };
+  /**
+   * The fields that are digits of the encoded product from the
+   * least significant to the most, along with their index among
+   * every field.
+   */
+  pub(super) fn encoded(&self) -> Vec<(usize, &FieldInfo)> {
+    let mut fields: Vec<_> = self
+      .fields()
+      .iter()
+      .enumerate()
+      .filter(|(_, f)| f.role != FieldRole::Tag)
+      .collect();
+
+    if self.order() == FieldOrder::Lexicographic {
+      fields.reverse();
     }
 
-    quote! { (#tokens) }
+    fields
   }
 
-  pub fn field_destructor(&self) -> TokenStream {
-    let with_iterator = |iter: &mut dyn Iterator<Item = TokenStream>| {
-      let mut tokens = quote! {};
+  pub fn calc_size(&self, krate: &TokenStream) -> TokenStream {
+    // Without any fields there's a single value, the same
+    // as a unit struct.
+    let fields = match self.encoded() {
+      fields if fields.is_empty() => return quote! { 1 },
+      fields => fields,
+    };
 
-      if let Some(name) = iter.next() {
-        tokens = quote! { #tokens #name };
-      }
+    let head = reprs_of(&fields[0].1.ty, krate);
+    let mut tokens = quote! { (#head) };
 
-      for name in iter {
-        tokens = quote! { #tokens, #name };
-      }
+    for (_, field) in fields[1..].iter() {
+      let reprs = reprs_of(&field.ty, krate);
+      tokens = quote! { #tokens * (#reprs) };
+    }
 
-      tokens
-    };
+    quote! { (#tokens) }
+  }
+
+  /**
+   * A pattern binding each field to a hygienic local, the names
+   * of these locals line up with `branch_size`.
+   */
+  pub fn field_destructor(&self) -> TokenStream {
+    self.field_destructor_with(field_ident)
+  }
 
+  /**
+   * Like `field_destructor`, binding each field to the local
+   * named by `local` for the index of that field.
+   */
+  pub fn field_destructor_with(&self, local: impl Fn(usize) -> Ident) -> TokenStream {
     match self {
       FieldCodegen::Fieldless => {
         quote! {}
       }
-      FieldCodegen::Product(fields) => {
-        let mut iter = fields.iter().enumerate().map(|(i, _)| anon_ident(i));
-        let tokens = with_iterator(&mut iter);
-        quote! { (#tokens) }
+      FieldCodegen::Product(fields, _) => {
+        let names = (0..fields.len()).map(local);
+        quote! { ( #(#names),* ) }
       }
-      FieldCodegen::ProductNamed(fields) => {
-        let mut iter = fields.iter().map(|(ident, _)| quote! { #ident });
-        let tokens = with_iterator(&mut iter);
-        quote! { { #tokens } }
+      FieldCodegen::ProductNamed(fields, _) => {
+        let names = fields.iter().enumerate().map(|(i, field)| {
+          let ident = &field.ident;
+          let local = local(i);
+          quote! { #ident: #local }
+        });
+        quote! { { #(#names),* } }
       }
     }
   }
 
-  pub fn branch_size(&self, generic: &Ident) -> (TokenStream, TokenStream) {
-    let with_iterator = |iter: &mut dyn Iterator<Item = (TokenStream, &Type)>| {
-      let (mut tokens, mut previous_type) = if let Some((name, own_type)) = iter.next() {
-        let tokens = quote! { #name.into_finite::<#generic>()? };
-        (tokens, own_type)
-      } else {
-        return (quote! {}, quote! { #generic::ONE });
-      };
-
-      let mut size_stmt = quote! {};
-      let mut size_expr = quote! { #generic::ONE };
-
-      for (name, own_type) in iter {
-        let ident_name = format!("size_type_{}", name);
-        let size_ident = Ident::new(&ident_name, Span::call_site());
-        size_stmt = quote! {
-          #size_stmt
-          let #size_ident = #generic::from_usize(#previous_type::REPRS)?;
-        };
+  /**
+   * The index of each encoded field, from the most significant
+   * to the least, which is the order in which they're compared.
+   */
+  pub fn significance(&self) -> Vec<usize> {
+    self
+      .encoded()
+      .iter()
+      .rev()
+      .map(|(index, _)| *index)
+      .collect()
+  }
 
-        size_expr = quote! { #size_expr * size_#previous_type };
-        tokens = quote! {
-          #tokens + (#size_ident * #name.into_finite::<#generic>()?)
-        };
-        previous_type = own_type;
-      }
+  /**
+   * Encodes the fields bound by `field_destructor`, the place
+   * value of each field is a product of constants which is only
+   * checked to fit in the generic type when the field isn't
+   * zero. The sum of the fields is checked, so a number too
+   * large for the generic type returns `None`.
+   */
+  pub fn branch_size(&self, generic: &Ident, krate: &TokenStream) -> (TokenStream, TokenStream) {
+    if self.encoded().is_empty() {
+      return (quote! {}, quote! { #generic::ZERO });
+    }
 
-      (size_stmt, quote! { ( #tokens ) })
+    let fields = self.encoded();
+    let encode_digit = |digit: usize| {
+      let (index, field) = fields[digit];
+      encode_field(index, field, generic, krate)
     };
 
-    match self {
-      FieldCodegen::Fieldless => (quote! {}, quote! { #generic::from_usize(0)? }),
-      FieldCodegen::Product(fields) => {
-        let mut iter = fields.iter().enumerate().map(|(i, t)| {
-          let name = anon_ident(i);
-          (quote! { #name }, t)
-        });
-
-        with_iterator(&mut iter)
-      }
-      FieldCodegen::ProductNamed(fields) => {
-        let mut iter = fields.iter().map(|(ident, t)| (quote! { #ident }, t));
-
-        with_iterator(&mut iter)
-      }
+    let option = idents::option_path();
+    let mut tokens = encode_digit(0);
+
+    for digit in 1..fields.len() {
+      let place = self.place_value(digit, krate);
+      let number = encode_digit(digit);
+      let digit_value = idents::local(&format!("digit_{}", digit));
+
+      // A zero digit adds nothing, however large its place.
+      let value = quote! {{
+        let #digit_value = #number;
+        if #digit_value == #generic::ZERO {
+          #generic::ZERO
+        } else if #place > #generic::MAX_USIZE {
+          return #option::None;
+        } else {
+          #krate::FiniteValue::checked_mul(#generic::constant(#place), #digit_value)?
+        }
+      }};
+      tokens = quote! { #krate::FiniteValue::checked_add(#tokens, #value)? };
     }
+
+    (quote! {}, quote! { ( #tokens ) })
   }
 
+  /**
+   * Decodes each field from the digits of `value` given by
+   * `split_digits`.
+   */
   pub fn branch_construction(
     &self,
     value: &TokenStream,
     generic_type: &Ident,
+    krate: &TokenStream,
   ) -> (TokenStream, TokenStream, Option<TokenStream>) {
-    let with_iterator = |iter: &mut dyn Iterator<Item = (TokenStream, &Type)>| {
-      let mut field_sizes = quote! {};
-      let mut assignments = quote! {};
-      let mut iter = iter.peekable();
-
-      let create_field_size_ident =
-        |n: &TokenStream| Ident::new(&format!("size_{}", n), Span::call_site());
-
-      let mut add_field_size = |name: &Ident, ty: &Type| {
-        field_sizes = quote! {
-          #field_sizes
-          let #name = #generic_type::from_usize(#ty::REPRS)?;
-        };
-      };
+    if self.encoded().is_empty() {
+      let cond = quote! { #value == #generic_type::ZERO };
+      return (quote! {}, self.field_destructor(), Some(cond));
+    }
 
-      let mut add_field_assignment = |name: &TokenStream, ty: &Type, arg| {
-        assignments = quote! {
-          #assignments
-          let #name = #ty::from_finite(#arg)?;
-        };
-      };
+    let stmts = self.split_digits(value, generic_type, krate, |index, field, digit| {
+      decode_field(index, field, digit, krate)
+    });
 
-      let mut args = quote! {};
-      let mut previous_size = quote! {};
+    (stmts, self.field_destructor(), None)
+  }
 
-      match iter.next() {
-        Some((n, t)) => {
-          let ident = create_field_size_ident(&n);
-          add_field_size(&ident, &t);
-          add_field_assignment(&n, &t, quote! { #value % #ident });
-          args = quote! { #args #n };
-          previous_size = quote! { #previous_size #ident };
-        }
-        None => {
-          let cond = quote! { #value > #generic_type::MIN };
-          return (quote! {}, quote! {}, Some(cond));
-        }
-      };
+  /**
+   * Returns why `value` doesn't decode, given it's one of the
+   * representations of these fields, by checking the digit of
+   * every field with `check_field`.
+   */
+  pub fn branch_error(
+    &self,
+    value: &TokenStream,
+    generic_type: &Ident,
+    krate: &TokenStream,
+  ) -> TokenStream {
+    let stmts = self.split_digits(value, generic_type, krate, |_, field, digit| {
+      check_field(field, digit, krate)
+    });
+    field_error_stmts(stmts, krate)
+  }
 
-      while let Some((n, t)) = iter.next() {
-        let ident = create_field_size_ident(&n);
-        add_field_size(&ident, &t);
+  /**
+   * Splits `value` into the digit of each field, the least
+   * significant field is the remainder after dividing by its
+   * number of representations & the most significant is what
+   * remains. Each digit is passed to `field_stmts`.
+   */
+  fn split_digits(
+    &self,
+    value: &TokenStream,
+    generic_type: &Ident,
+    krate: &TokenStream,
+    field_stmts: impl Fn(usize, &FieldInfo, &TokenStream) -> TokenStream,
+  ) -> TokenStream {
+    let fields = self.encoded();
+    let rest = idents::local("rest");
+    let mut stmts = quote! {
+      let #rest = #value;
+    };
 
-        let value_passed = if iter.peek().is_none() {
-          quote! { #value / #ident }
-        } else {
-          quote! { (#value / (#previous_size)) % #ident }
-        };
+    for (digit, (index, field)) in fields.iter().enumerate() {
+      let ty = &field.ty;
 
-        add_field_assignment(&n, &t, value_passed);
+      let value_passed = if digit == fields.len() - 1 {
+        quote! { #rest }
+      } else {
+        // A radix larger than the generic type is larger than
+        // any value, leaving all of it for this field.
+        let digit_value = idents::local(&format!("digit_{}", digit));
+        let radix = reprs_of(ty, krate);
+        stmts = quote! {
+          #stmts
+          let #digit_value = if #radix > #generic_type::MAX_USIZE {
+            #rest
+          } else {
+            #rest % #generic_type::constant(#radix)
+          };
+          let #rest = if #radix > #generic_type::MAX_USIZE {
+            #generic_type::ZERO
+          } else {
+            #rest / #generic_type::constant(#radix)
+          };
+        };
+        quote! { #digit_value }
+      };
 
-        args = quote! { #args, #n };
-        previous_size = quote! { #previous_size * #ident };
-      }
+      let field_stmts = field_stmts(*index, field, &value_passed);
 
-      let stmts = quote! {
-        #field_sizes
-        #assignments
+      stmts = quote! {
+        #stmts
+        #field_stmts
       };
+    }
 
-      (stmts, quote! { #args }, None)
-    };
+    stmts
+  }
 
-    match self {
-      FieldCodegen::Fieldless => (
-        quote! {},
-        quote! {},
-        Some(quote! { #value == #generic_type::MIN }),
-      ),
-      FieldCodegen::Product(fields) => {
-        let mut iter = fields.iter().enumerate().map(|(i, t)| {
-          let name = anon_ident(i);
-          (quote! { #name }, t)
-        });
+  /**
+   * The product of the number of representations of every
+   * encoded field before `digit`, as a `usize` constant.
+   */
+  fn place_value(&self, digit: usize, krate: &TokenStream) -> TokenStream {
+    let fields = self.encoded();
+    let mut reprs = fields[..digit].iter().map(|(_, f)| reprs_of(&f.ty, krate));
+    let head = reprs.next().unwrap_or_else(|| quote! { 1 });
+    let product = reprs.fold(head, |tokens, r| quote! { #tokens * #r });
+    quote! { (#product) }
+  }
+}
 
-        let (s, args, cond) = with_iterator(&mut iter);
-        (s, quote! { ( #args ) }, cond)
+/**
+ * Encodes the field at `index` bound by `field_destructor`,
+ * reading a union by the tag in its sibling field, which the
+ * container's `FiniteTagged` impl promises is correct.
+ */
+pub(super) fn encode_field(
+  index: usize,
+  field: &FieldInfo,
+  generic: &Ident,
+  krate: &TokenStream,
+) -> TokenStream {
+  let name = field_ident(index);
+  let ty = &field.ty;
+
+  match field.role {
+    FieldRole::Union { tag } => {
+      let tag = field_ident(tag);
+      quote_spanned! { ty.span() =>
+        unsafe { <#ty as #krate::FiniteUnionEncoding>::into_finite_arm::<#generic>(#name, #tag)? }
       }
-      FieldCodegen::ProductNamed(fields) => {
-        let mut iter = fields.iter().map(|(n, t)| (quote! { #n }, t));
+    }
+    _ => quote_spanned! { ty.span() =>
+      <#ty as #krate::FiniteEncoding>::into_finite::<#generic>(#name)?
+    },
+  }
+}
 
-        let (s, args, cond) = with_iterator(&mut iter);
-        (s, quote! { { #args } }, cond)
+/**
+ * Binds the field at `index` decoded from `value`, along with
+ * the tag of its sibling field when it's a union.
+ */
+pub(super) fn decode_field(
+  index: usize,
+  field: &FieldInfo,
+  value: &TokenStream,
+  krate: &TokenStream,
+) -> TokenStream {
+  let name = field_ident(index);
+  let ty = &field.ty;
+
+  match field.role {
+    FieldRole::Union { tag } => {
+      let tag = field_ident(tag);
+      quote_spanned! { ty.span() =>
+        let (#tag, #name) = <#ty as #krate::FiniteUnionDecoding>::from_finite_arm(#value)?;
       }
     }
+    _ => quote_spanned! { ty.span() =>
+      let #name = <#ty as #krate::FiniteDecoding>::from_finite(#value)?;
+    },
   }
 }
 
-fn anon_ident(index: usize) -> TokenStream {
-  let name = format!("unknown_{}", index);
-  let ident = Ident::new(&name, Span::call_site());
-  quote! { #ident }
+/**
+ * Checks the field decodes from `value`, noting a digit its
+ * type reserves & returning as soon as any other digit fails.
+ */
+pub(super) fn check_field(
+  field: &FieldInfo,
+  value: &TokenStream,
+  krate: &TokenStream,
+) -> TokenStream {
+  let ty = &field.ty;
+  let reserved = idents::local("reserved");
+
+  match field.role {
+    FieldRole::Union { .. } => quote_spanned! { ty.span() =>
+      if <#ty as #krate::FiniteUnionDecoding>::from_finite_arm(#value).is_none() {
+        return #krate::DecodeError::Invalid;
+      }
+    },
+    _ => quote_spanned! { ty.span() =>
+      match <#ty as #krate::FiniteDecoding>::try_from_finite(#value) {
+        ::core::result::Result::Ok(_) => {}
+        ::core::result::Result::Err(#krate::DecodeError::Reserved) => #reserved = true,
+        ::core::result::Result::Err(_) => return #krate::DecodeError::Invalid,
+      }
+    },
+  }
 }
 
-fn seperate_type(t: &Type) -> TokenStream {
-  match t {
-    Type::Path(typepath) => {
-      let mut typepath = typepath.clone();
+/**
+ * Wraps the `check_field` statements of every field, a number
+ * is only reserved if every field rejecting it reserves it.
+ */
+pub(super) fn field_error_stmts(checks: TokenStream, krate: &TokenStream) -> TokenStream {
+  let reserved = idents::local("reserved");
+  quote! {
+    #[allow(unused_mut)]
+    let mut #reserved = false;
+    #checks
+    return match #reserved {
+      true => #krate::DecodeError::Reserved,
+      false => #krate::DecodeError::Invalid,
+    };
+  }
+}
 
-      // ensure all the generic types have :: place betwen the type & the arguments
-      for segment in typepath.path.segments.iter_mut() {
-        if let syn::PathArguments::AngleBracketed(a) = &mut segment.arguments {
-          a.colon2_token = Some(syn::token::Colon2::default());
-        }
-      }
+pub(super) fn field_ident(index: usize) -> Ident {
+  idents::local(&format!("field_{}", index))
+}
 
-      quote! { #typepath }
-    }
-    other => quote! { #other },
-  }
+/**
+ * Spanned to the field's type, so a type missing an impl is
+ * reported on the field rather than the derive.
+ */
+pub(super) fn reprs_of(t: &Type, krate: &TokenStream) -> TokenStream {
+  quote_spanned! { t.span() => <#t as #krate::FiniteRepr>::REPRS }
 }
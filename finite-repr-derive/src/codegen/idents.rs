@@ -0,0 +1,57 @@
+use proc_macro2::{Ident, Span, TokenStream};
+use proc_macro_crate::{crate_name, FoundCrate};
+use quote::quote;
+use syn::Path;
+
+/**
+ * Identifiers introduced by generated code are created with a
+ * mixed site span so they never capture the callers locals, and
+ * are mangled as mixed site hygiene doesn't extend to items such
+ * as constants which could otherwise turn a binding into a pattern.
+ */
+pub fn local(name: &str) -> Ident {
+  Ident::new(&format!("__finite_{}", name), Span::mixed_site())
+}
+
+/**
+ * Generic parameters aren't covered by mixed site hygiene, so
+ * instead the name is mangled to avoid shadowing any generic
+ * parameters declared on the type being derived.
+ */
+pub fn generic_param() -> Ident {
+  Ident::new("__FiniteValueT", Span::call_site())
+}
+
+/**
+ * The path to `finite_repr` as seen from the crate invoking the
+ * derive, which accounts for the dependency being renamed unless
+ * the path was given explicitly with `#[finite(crate = "...")]`.
+ */
+pub fn crate_path(overridden: Option<&Path>) -> TokenStream {
+  if let Some(path) = overridden {
+    return quote! { #path };
+  }
+
+  // `Itself` is also reported for the crate's own integration
+  // tests & doctests, where `crate` is the test crate.
+  match crate_name("finite_repr") {
+    Ok(FoundCrate::Itself) => quote! { ::finite_repr },
+    Ok(FoundCrate::Name(name)) => {
+      let ident = Ident::new(&name, Span::call_site());
+      quote! { ::#ident }
+    }
+    Err(_) => quote! { ::finite_repr },
+  }
+}
+
+pub fn option_path() -> TokenStream {
+  quote! { ::core::option::Option }
+}
+
+/**
+ * The hidden inherent const holding the offset of each
+ * variant of an enum, used when decoding.
+ */
+pub fn offsets_const() -> Ident {
+  Ident::new("__FINITE_OFFSETS", Span::call_site())
+}
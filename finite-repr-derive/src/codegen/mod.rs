@@ -1,3 +1,7 @@
+pub mod bitpacked_codegen;
 pub mod bounds;
 pub mod enum_codegen;
 pub mod field_codegen;
+pub mod idents;
+pub mod table_codegen;
+pub mod union_codegen;
@@ -0,0 +1,142 @@
+use super::field_codegen::{field_ident, FieldCodegen};
+use proc_macro2::TokenStream;
+use quote::{quote, quote_spanned};
+use syn::spanned::Spanned;
+use syn::Type;
+
+/**
+ * The index of the fields bound by `field_destructor`, this is
+ * evaluated in a `const fn` so traits can't be used. Fields are
+ * either `bool`, `u8` or a type that also has a table.
+ */
+pub fn index_expression(codegen: &FieldCodegen, krate: &TokenStream) -> TokenStream {
+  let fields = codegen.encoded();
+  if fields.is_empty() {
+    return quote! { 0 };
+  }
+
+  let mut tokens = quote! {};
+  for (digit, (index, field)) in fields.iter().enumerate() {
+    let name = field_ident(*index);
+    let ty = &field.ty;
+    let field_index = match primitive(ty) {
+      Some(_) => quote! { (*#name as usize) },
+      None => quote_spanned! { ty.span() => <#ty>::index(#name) },
+    };
+
+    tokens = match digit {
+      0 => field_index,
+      _ => {
+        let place = place_value(codegen, digit, krate);
+        quote! { #tokens + #field_index * (#place) }
+      }
+    };
+  }
+
+  tokens
+}
+
+/**
+ * The fields of the value at `index`, in the same form as the
+ * pattern returned by `field_destructor`.
+ */
+pub fn construction_arguments(
+  codegen: &FieldCodegen,
+  index: &TokenStream,
+  krate: &TokenStream,
+) -> TokenStream {
+  let digits = codegen.encoded();
+  let values = codegen
+    .fields()
+    .iter()
+    .enumerate()
+    .map(|(field_index, field)| {
+      let digit = digits.iter().position(|(i, _)| *i == field_index).unwrap();
+      let ty = &field.ty;
+      let reprs = quote_spanned! { ty.span() => <#ty as #krate::FiniteRepr>::REPRS };
+      let digit_value = match digit {
+        0 => quote! { #index % #reprs },
+        _ => {
+          let place = place_value(codegen, digit, krate);
+          quote! { (#index / (#place)) % #reprs }
+        }
+      };
+
+      match primitive(ty) {
+        Some(Primitive::Bool) => quote! { (#digit_value) != 0 },
+        Some(Primitive::U8) => quote! { (#digit_value) as ::core::primitive::u8 },
+        None => quote_spanned! { ty.span() => <#ty>::ALL[#digit_value] },
+      }
+    });
+
+  match codegen {
+    FieldCodegen::Fieldless => quote! {},
+    FieldCodegen::Product(..) => quote! { ( #(#values),* ) },
+    FieldCodegen::ProductNamed(fields, _) => {
+      let names = fields.iter().map(|f| &f.ident);
+      quote! { { #(#names: #values),* } }
+    }
+  }
+}
+
+/**
+ * Asserts the type of each field uses all of its representations,
+ * a field that reserves some has a table shorter than the digit
+ * it's indexed by.
+ */
+pub fn reserve_checks(codegen: &FieldCodegen, krate: &TokenStream) -> TokenStream {
+  let checks = codegen.encoded().into_iter().filter_map(|(_, field)| {
+    let ty = &field.ty;
+    let message = format!(
+      "`#[finite(table)]` doesn't support fields of `{}` as it reserves representations",
+      quote! { #ty }
+    );
+    match primitive(ty) {
+      Some(_) => None,
+      None => Some(quote_spanned! { ty.span() =>
+        const _: () = ::core::assert!(
+          <#ty as #krate::FiniteRepr>::USED_REPRS == <#ty as #krate::FiniteRepr>::REPRS,
+          #message
+        );
+      }),
+    }
+  });
+
+  quote! { #(#checks)* }
+}
+
+/**
+ * The product of the number of representations of every
+ * field less significant than the field at `digit`.
+ */
+fn place_value(codegen: &FieldCodegen, digit: usize, krate: &TokenStream) -> TokenStream {
+  let fields = codegen.encoded();
+  let mut reprs = fields[..digit].iter().map(|(_, field)| {
+    let ty = &field.ty;
+    quote_spanned! { ty.span() => <#ty as #krate::FiniteRepr>::REPRS }
+  });
+
+  let head = reprs.next().unwrap_or_else(|| quote! { 1 });
+  reprs.fold(head, |tokens, field| quote! { #tokens * #field })
+}
+
+enum Primitive {
+  Bool,
+  U8,
+}
+
+fn primitive(ty: &Type) -> Option<Primitive> {
+  match ty {
+    Type::Path(path) if path.qself.is_none() => {
+      let ident = path.path.get_ident()?;
+      if ident == "bool" {
+        Some(Primitive::Bool)
+      } else if ident == "u8" {
+        Some(Primitive::U8)
+      } else {
+        None
+      }
+    }
+    _ => None,
+  }
+}
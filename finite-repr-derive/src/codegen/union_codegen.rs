@@ -0,0 +1,63 @@
+use super::enum_codegen::sum;
+use super::field_codegen::reprs_of;
+use proc_macro2::{Ident, TokenStream};
+use syn::{Expr, Type};
+
+/**
+ * The arms of a union in declaration order, each arm is laid
+ * out like the variant of an enum with a single field.
+ */
+pub struct UnionCodegen {
+  tag_type: Type,
+  arms: Vec<ArmInfo>,
+}
+
+pub struct ArmInfo {
+  pub ident: Ident,
+  pub ty: Type,
+  /**
+   * The value of the tag when this arm is initialised.
+   */
+  pub tag: Expr,
+}
+
+impl UnionCodegen {
+  pub fn new(tag_type: Type, arms: Vec<ArmInfo>) -> Self {
+    UnionCodegen { tag_type, arms }
+  }
+
+  pub fn tag_type(&self) -> &Type {
+    &self.tag_type
+  }
+
+  pub fn arms(&self) -> &[ArmInfo] {
+    &self.arms
+  }
+
+  pub fn size_expression(&self, krate: &TokenStream) -> TokenStream {
+    sum(self.arms.iter().map(|arm| reprs_of(&arm.ty, krate)))
+  }
+
+  /**
+   * The smallest number the arm at `index` is encoded as.
+   */
+  pub fn floor_expression(&self, index: usize, krate: &TokenStream) -> TokenStream {
+    sum(
+      self.arms[..index]
+        .iter()
+        .map(|arm| reprs_of(&arm.ty, krate)),
+    )
+  }
+
+  /**
+   * Exclusive upper bound of the numbers the arm at `index`
+   * is encoded as.
+   */
+  pub fn ceil_expression(&self, index: usize, krate: &TokenStream) -> TokenStream {
+    sum(
+      self.arms[..=index]
+        .iter()
+        .map(|arm| reprs_of(&arm.ty, krate)),
+    )
+  }
+}
@@ -0,0 +1,456 @@
+use crate::attributes::{
+  AttributeError, ContainerAttributes, FieldAttributes, InvalidValue, VariantAttributes,
+};
+use crate::codegen::enum_codegen::{EnumCodegen, PrimitiveRepr, VariantInfo};
+use crate::codegen::field_codegen::{FieldCodegen, FieldOrder, FieldRole};
+use crate::codegen::idents;
+use crate::codegen::union_codegen::{ArmInfo, UnionCodegen};
+use proc_macro2::{Ident, Span, TokenStream};
+use syn::spanned::Spanned;
+use syn::{
+  Data, DataEnum, DataUnion, DeriveInput, Expr, ExprLit, Fields, Generics, Lit, Member, Meta,
+  NestedMeta, Type,
+};
+
+pub use self::error::*;
+
+/**
+ * The analysis of a type shared by each derive, so the input
+ * is only parsed once when several impls are generated at once
+ * & every impl agrees on the `#[finite(...)]` attributes.
+ */
+pub struct Container<'a> {
+  pub ident: &'a Ident,
+  pub generics: &'a Generics,
+  pub krate: TokenStream,
+  pub data: ContainerData,
+  /**
+   * Set with `#[finite(table)]`, values are decoded by looking
+   * them up in a generated table of every value.
+   */
+  pub table: bool,
+  /**
+   * Set with `#[finite(fits = "u16")]`, the target the type
+   * is checked against at compile time.
+   */
+  pub fits: Option<Type>,
+  /**
+   * Set with `#[finite(fallback)]` on a variant, the variant
+   * decoded from numbers that don't decode otherwise.
+   */
+  pub fallback: Option<Ident>,
+  /**
+   * Set with `#[finite(reserve = 16)]`, the fixed number of
+   * representations of the type.
+   */
+  pub reserve: Option<usize>,
+}
+
+pub enum ContainerData {
+  Enum(EnumCodegen),
+  Struct(FieldCodegen),
+  Union(UnionCodegen),
+  /**
+   * A fieldless enum encoded by its discriminants, set
+   * with `#[finite(discriminant)]`.
+   */
+  Discriminant(EnumCodegen),
+  /**
+   * A struct whose fields each occupy a whole number of bits,
+   * set with `#[finite(bitpacked)]`.
+   */
+  Bitpacked(FieldCodegen),
+}
+
+impl<'a> Container<'a> {
+  pub fn from_derive_input(ast: &'a DeriveInput) -> Result<Self, ContainerError> {
+    let attributes = ContainerAttributes::from_attributes(&ast.attrs)?;
+    let krate = idents::crate_path(attributes.crate_path.as_ref());
+    let order = match attributes.order {
+      Some((_, order)) => order,
+      None => FieldOrder::LeastSignificantFirst,
+    };
+
+    let mut fallback = None;
+    let data = match &ast.data {
+      Data::Enum(data_enum) => {
+        forbid_option(attributes.tag.as_ref().map(|t| t.span), "tag", "enum")?;
+        forbid_option(attributes.bitpacked, "bitpacked", "enum")?;
+        let mut variants = vec![];
+        let mut other = false;
+        for (index, variant) in data_enum.variants.iter().enumerate() {
+          let variant_attributes = VariantAttributes::from_attributes(&variant.attrs)?;
+          if let Some(span) = variant_attributes.other {
+            if variant.fields.len() != 1 {
+              return Err(ContainerError::OtherFields(span));
+            }
+            if index + 1 != data_enum.variants.len() {
+              return Err(ContainerError::OtherNotLast(span));
+            }
+            other = true;
+          }
+          if let Some(span) = variant_attributes.fallback {
+            if !variant.fields.is_empty() {
+              return Err(ContainerError::FallbackWithFields(span));
+            }
+            if fallback.is_some() {
+              let error = AttributeError::DuplicateAttribute(span, "fallback".to_string());
+              return Err(ContainerError::Attribute(error));
+            }
+            fallback = Some(variant.ident.clone());
+          }
+          if attributes.discriminant.is_some() && !variant.fields.is_empty() {
+            return Err(ContainerError::DiscriminantWithFields(
+              variant.fields.span(),
+            ));
+          }
+          variants.push(VariantInfo {
+            ident: variant.ident.clone(),
+            repr: field_codegen(&variant.fields, order)?,
+          });
+        }
+        match (attributes.discriminant, primitive_repr(ast, data_enum)) {
+          (Some(_), _) => ContainerData::Discriminant(EnumCodegen::new(variants)),
+          (None, Some(primitive)) => {
+            ContainerData::Enum(EnumCodegen::with_primitive_repr(variants, primitive))
+          }
+          (None, None) => {
+            let mut codegen = EnumCodegen::new(variants);
+            if other {
+              codegen.mark_other();
+            }
+            ContainerData::Enum(codegen)
+          }
+        }
+      }
+      Data::Struct(data_struct) => {
+        forbid_option(attributes.tag.as_ref().map(|t| t.span), "tag", "struct")?;
+        forbid_option(attributes.discriminant, "discriminant", "struct")?;
+        let codegen = field_codegen(&data_struct.fields, order)?;
+        match attributes.bitpacked {
+          Some(span) if attributes.reserve.is_some() => {
+            return Err(ContainerError::ReserveWithBitpacked(span));
+          }
+          Some(_) => ContainerData::Bitpacked(codegen),
+          None => ContainerData::Struct(codegen),
+        }
+      }
+      Data::Union(data_union) => {
+        forbid_option(attributes.reserve.map(|(span, _)| span), "reserve", "union")?;
+        forbid_option(attributes.discriminant, "discriminant", "union")?;
+        forbid_option(attributes.bitpacked, "bitpacked", "union")?;
+        forbid_option(attributes.order.map(|(span, _)| span), "order", "union")?;
+        let tag_type = match &attributes.tag {
+          Some(tag) => tag.parse(InvalidValue::TagType)?,
+          None => return Err(ContainerError::MissingTagType(data_union.union_token.span)),
+        };
+        ContainerData::Union(union_codegen(tag_type, data_union)?)
+      }
+    };
+
+    if let Some(span) = attributes.table {
+      check_table(span, ast, &data)?;
+    }
+
+    // The check is a constant item, which can't name the
+    // generic parameters of the type.
+    if let Some((span, _)) = &attributes.fits {
+      if !ast.generics.params.is_empty() {
+        return Err(ContainerError::FitsWithGenerics(*span));
+      }
+    }
+
+    Ok(Container {
+      ident: &ast.ident,
+      generics: &ast.generics,
+      krate,
+      data,
+      table: attributes.table.is_some(),
+      fits: attributes.fits.map(|(_, ty)| ty),
+      fallback,
+      reserve: attributes.reserve.map(|(_, reserve)| reserve),
+    })
+  }
+
+  /**
+   * Whether a field of the struct, or of any variant, holds
+   * a tagged union.
+   */
+  pub fn holds_union(&self) -> bool {
+    let holds =
+      |codegen: &FieldCodegen| codegen.fields().iter().any(|f| f.role != FieldRole::Value);
+
+    match &self.data {
+      ContainerData::Struct(codegen) | ContainerData::Bitpacked(codegen) => holds(codegen),
+      ContainerData::Enum(codegen) | ContainerData::Discriminant(codegen) => {
+        codegen.variants().iter().any(|v| holds(&v.repr))
+      }
+      ContainerData::Union(_) => false,
+    }
+  }
+}
+
+fn forbid_option(
+  span: Option<Span>,
+  name: &str,
+  position: &'static str,
+) -> Result<(), ContainerError> {
+  match span {
+    Some(span) => {
+      let error = AttributeError::UnsupportedPosition(span, name.to_string(), position);
+      Err(ContainerError::Attribute(error))
+    }
+    None => Ok(()),
+  }
+}
+
+/**
+ * The table is built in a const context where only the
+ * concrete layout of the type can be used.
+ */
+fn check_table(span: Span, ast: &DeriveInput, data: &ContainerData) -> Result<(), ContainerError> {
+  if ast.generics.type_params().next().is_some() {
+    return Err(ContainerError::TableWithGenerics(span));
+  }
+
+  let has_union = |fields: &FieldCodegen| {
+    let mut roles = fields.fields().iter().map(|f| f.role);
+    roles.any(|role| role != FieldRole::Value)
+  };
+
+  match data {
+    ContainerData::Enum(codegen) if codegen.has_other() => {
+      Err(ContainerError::TableWithOther(span))
+    }
+    ContainerData::Enum(codegen) if codegen.variants().iter().any(|v| has_union(&v.repr)) => {
+      Err(ContainerError::TableWithUnion(span))
+    }
+    ContainerData::Struct(fields) if has_union(fields) => Err(ContainerError::TableWithUnion(span)),
+    ContainerData::Enum(_) | ContainerData::Struct(_) => Ok(()),
+    ContainerData::Union(_) => {
+      let error = AttributeError::UnsupportedPosition(span, "table".to_string(), "union");
+      Err(ContainerError::Attribute(error))
+    }
+    ContainerData::Discriminant(_) => Err(ContainerError::TableWithDiscriminant(span)),
+    ContainerData::Bitpacked(_) => Err(ContainerError::TableWithBitpacked(span)),
+  }
+}
+
+/**
+ * Recognises a fieldless enum with a `#[repr(uN)]` whose
+ * discriminants are literals (or implicit) and each one more
+ * than the last, as these can be cast to their encoding.
+ */
+fn primitive_repr(ast: &DeriveInput, data_enum: &DataEnum) -> Option<PrimitiveRepr> {
+  const UNSIGNED: [&str; 5] = ["u8", "u16", "u32", "u64", "usize"];
+
+  let ty = ast
+    .attrs
+    .iter()
+    .filter(|attr| attr.path.is_ident("repr"))
+    .filter_map(|attr| match attr.parse_meta() {
+      Ok(Meta::List(list)) => Some(list.nested),
+      _ => None,
+    })
+    .flatten()
+    .find_map(|nested| match nested {
+      NestedMeta::Meta(Meta::Path(path)) => path
+        .get_ident()
+        .filter(|ident| UNSIGNED.iter().any(|ty| *ident == ty))
+        .cloned(),
+      _ => None,
+    })?;
+
+  let mut offset = None;
+  let mut next = 0u64;
+
+  for variant in data_enum.variants.iter() {
+    if !variant.fields.is_empty() {
+      return None;
+    }
+
+    let discriminant = match &variant.discriminant {
+      None => next,
+      Some((
+        _,
+        Expr::Lit(ExprLit {
+          lit: Lit::Int(int), ..
+        }),
+      )) => int.base10_parse().ok()?,
+      Some(_) => return None,
+    };
+
+    if offset.is_some() && discriminant != next {
+      return None;
+    }
+
+    offset.get_or_insert(discriminant);
+    next = discriminant.checked_add(1)?;
+  }
+
+  Some(PrimitiveRepr {
+    ty,
+    offset: offset?,
+  })
+}
+
+/**
+ * Resolves `#[finite(tag = field)]` on the fields of a struct
+ * or variant, to the sibling field holding the union's tag.
+ */
+fn field_codegen(fields: &Fields, order: FieldOrder) -> Result<FieldCodegen, ContainerError> {
+  let mut codegen = FieldCodegen::from_fields(fields, order);
+
+  for (index, field) in fields.iter().enumerate() {
+    let tag = match FieldAttributes::from_attributes(&field.attrs)?.tag {
+      Some(tag) => tag,
+      None => continue,
+    };
+
+    let member: Member = tag.parse(InvalidValue::TagField)?;
+    let tag_index = match codegen.position_of(&member) {
+      Some(tag_index) if tag_index != index => tag_index,
+      _ => return Err(ContainerError::UnknownTagField(tag.span)),
+    };
+
+    if codegen.fields()[tag_index].role != FieldRole::Value {
+      return Err(ContainerError::TagFieldInUse(tag.span));
+    }
+
+    codegen.tag_union(index, tag_index);
+  }
+
+  Ok(codegen)
+}
+
+fn union_codegen(tag_type: Type, data_union: &DataUnion) -> Result<UnionCodegen, ContainerError> {
+  let mut arms = vec![];
+
+  for field in data_union.fields.named.iter() {
+    let tag = match FieldAttributes::from_attributes(&field.attrs)?.tag {
+      Some(tag) => tag.parse(InvalidValue::Tag)?,
+      None => return Err(ContainerError::MissingArmTag(field.span())),
+    };
+
+    arms.push(ArmInfo {
+      ident: field.ident.clone().unwrap(),
+      ty: field.ty.clone(),
+      tag,
+    });
+  }
+
+  Ok(UnionCodegen::new(tag_type, arms))
+}
+
+mod error {
+  use crate::attributes::AttributeError;
+  use crate::impl_error::CompilerError;
+
+  pub enum ContainerError {
+    Attribute(AttributeError),
+    MissingTagType(proc_macro2::Span),
+    MissingArmTag(proc_macro2::Span),
+    UnknownTagField(proc_macro2::Span),
+    TagFieldInUse(proc_macro2::Span),
+    DiscriminantWithFields(proc_macro2::Span),
+    TableWithGenerics(proc_macro2::Span),
+    TableWithUnion(proc_macro2::Span),
+    TableWithDiscriminant(proc_macro2::Span),
+    TableWithBitpacked(proc_macro2::Span),
+    FitsWithGenerics(proc_macro2::Span),
+    FallbackWithFields(proc_macro2::Span),
+    OtherFields(proc_macro2::Span),
+    OtherNotLast(proc_macro2::Span),
+    TableWithOther(proc_macro2::Span),
+    ReserveWithBitpacked(proc_macro2::Span),
+  }
+
+  impl From<AttributeError> for ContainerError {
+    fn from(error: AttributeError) -> Self {
+      ContainerError::Attribute(error)
+    }
+  }
+
+  impl CompilerError for ContainerError {
+    fn compile_error(&self) -> proc_macro2::TokenStream {
+      match *self {
+        ContainerError::Attribute(ref error) => error.compile_error(),
+        ContainerError::MissingTagType(span) => {
+          quote::quote_spanned! {
+            span => compile_error!("unions need the type of their tag, as in `#[finite(tag = u8)]`");
+          }
+        }
+        ContainerError::MissingArmTag(span) => {
+          quote::quote_spanned! {
+            span => compile_error!("every arm of a union needs a tag, as in `#[finite(tag = 0)]`");
+          }
+        }
+        ContainerError::UnknownTagField(span) => {
+          quote::quote_spanned! {
+            span => compile_error!("expected the name of another field holding the tag");
+          }
+        }
+        ContainerError::DiscriminantWithFields(span) => {
+          quote::quote_spanned! {
+            span => compile_error!("`discriminant` is only supported on enums without fields");
+          }
+        }
+        ContainerError::TableWithGenerics(span) => {
+          quote::quote_spanned! {
+            span => compile_error!("`table` isn't supported on generic types");
+          }
+        }
+        ContainerError::TableWithUnion(span) => {
+          quote::quote_spanned! {
+            span => compile_error!("`table` isn't supported on types holding a tagged union");
+          }
+        }
+        ContainerError::TableWithDiscriminant(span) => {
+          quote::quote_spanned! {
+            span => compile_error!("`table` can't be combined with `discriminant`");
+          }
+        }
+        ContainerError::TableWithBitpacked(span) => {
+          quote::quote_spanned! {
+            span => compile_error!("`table` can't be combined with `bitpacked`");
+          }
+        }
+        ContainerError::FitsWithGenerics(span) => {
+          quote::quote_spanned! {
+            span => compile_error!("`fits` isn't supported on generic types");
+          }
+        }
+        ContainerError::FallbackWithFields(span) => {
+          quote::quote_spanned! {
+            span => compile_error!("`fallback` is only supported on variants without fields");
+          }
+        }
+        ContainerError::OtherFields(span) => {
+          quote::quote_spanned! {
+            span => compile_error!("`other` is only supported on variants with a single field, as in `Unknown(u32)`");
+          }
+        }
+        ContainerError::OtherNotLast(span) => {
+          quote::quote_spanned! {
+            span => compile_error!("`other` is only supported on the last variant");
+          }
+        }
+        ContainerError::TableWithOther(span) => {
+          quote::quote_spanned! {
+            span => compile_error!("`table` can't be combined with an `other` variant");
+          }
+        }
+        ContainerError::ReserveWithBitpacked(span) => {
+          quote::quote_spanned! {
+            span => compile_error!("`reserve` can't be combined with `bitpacked`");
+          }
+        }
+        ContainerError::TagFieldInUse(span) => {
+          quote::quote_spanned! {
+            span => compile_error!("this field already holds a tag or a union");
+          }
+        }
+      }
+    }
+  }
+}
@@ -0,0 +1,76 @@
+use crate::container::Container;
+use crate::{finite_decoding, finite_encoding, finite_repr};
+use proc_macro2::TokenStream;
+use quote::quote;
+
+pub use error::*;
+
+type ImplResult<T> = Result<T, DeriveFinite>;
+
+/**
+ * Derives `FiniteRepr`, `FiniteEncoding` & `FiniteDecoding`
+ * from a single analysis of the type.
+ */
+pub fn impl_derive(ast: &syn::DeriveInput) -> ImplResult<TokenStream> {
+  let container = Container::from_derive_input(ast)?;
+
+  let repr_impl = finite_repr::impl_container(&container)?;
+  let encoding_impl = finite_encoding::impl_container(&container)?;
+  let decoding_impl = finite_decoding::impl_container(&container)?;
+
+  Ok(quote! {
+    #repr_impl
+    #encoding_impl
+    #decoding_impl
+  })
+}
+
+mod error {
+  use crate::container::ContainerError;
+  use crate::finite_decoding::DeriveFiniteDecoding;
+  use crate::finite_encoding::DeriveFiniteEncoding;
+  use crate::finite_repr::DeriveFiniteRepr;
+  use crate::impl_error::CompilerError;
+
+  pub enum DeriveFinite {
+    Container(ContainerError),
+    Repr(DeriveFiniteRepr),
+    Encoding(DeriveFiniteEncoding),
+    Decoding(DeriveFiniteDecoding),
+  }
+
+  impl From<ContainerError> for DeriveFinite {
+    fn from(error: ContainerError) -> Self {
+      DeriveFinite::Container(error)
+    }
+  }
+
+  impl From<DeriveFiniteRepr> for DeriveFinite {
+    fn from(error: DeriveFiniteRepr) -> Self {
+      DeriveFinite::Repr(error)
+    }
+  }
+
+  impl From<DeriveFiniteEncoding> for DeriveFinite {
+    fn from(error: DeriveFiniteEncoding) -> Self {
+      DeriveFinite::Encoding(error)
+    }
+  }
+
+  impl From<DeriveFiniteDecoding> for DeriveFinite {
+    fn from(error: DeriveFiniteDecoding) -> Self {
+      DeriveFinite::Decoding(error)
+    }
+  }
+
+  impl CompilerError for DeriveFinite {
+    fn compile_error(&self) -> proc_macro2::TokenStream {
+      match self {
+        DeriveFinite::Container(error) => error.compile_error(),
+        DeriveFinite::Repr(error) => error.compile_error(),
+        DeriveFinite::Encoding(error) => error.compile_error(),
+        DeriveFinite::Decoding(error) => error.compile_error(),
+      }
+    }
+  }
+}
@@ -0,0 +1,65 @@
+use crate::container::Container;
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::spanned::Spanned;
+
+pub use error::*;
+
+type ImplResult<T> = Result<T, DeriveFiniteCompact>;
+
+pub fn impl_derive(ast: &syn::DeriveInput) -> ImplResult<TokenStream> {
+  let container = Container::from_derive_input(ast)?;
+  impl_container(&container)
+}
+
+/**
+ * Implements `FiniteCompact` by picking the integer from the
+ * bytes the type needs, which is a constant expression that
+ * can't depend on generic parameters.
+ */
+pub fn impl_container(container: &Container) -> ImplResult<TokenStream> {
+  let name = container.ident;
+  let krate = &container.krate;
+
+  if let Some(param) = container.generics.params.first() {
+    return Err(DeriveFiniteCompact::UnsupportedGenerics(param.span()));
+  }
+
+  Ok(quote! {
+    #[automatically_derived]
+    impl #krate::FiniteCompact for #name {
+      type Compact = <#krate::Width<
+        { #krate::bytes_required(<#name as #krate::FiniteRepr>::REPRS) }
+      > as #krate::CompactWidth>::Int;
+    }
+  })
+}
+
+mod error {
+  use crate::container::ContainerError;
+  use crate::impl_error::CompilerError;
+
+  pub enum DeriveFiniteCompact {
+    Container(ContainerError),
+    UnsupportedGenerics(proc_macro2::Span),
+  }
+
+  impl From<ContainerError> for DeriveFiniteCompact {
+    fn from(error: ContainerError) -> Self {
+      DeriveFiniteCompact::Container(error)
+    }
+  }
+
+  impl CompilerError for DeriveFiniteCompact {
+    fn compile_error(&self) -> proc_macro2::TokenStream {
+      match *self {
+        DeriveFiniteCompact::Container(ref error) => error.compile_error(),
+        DeriveFiniteCompact::UnsupportedGenerics(span) => {
+          quote::quote_spanned! {
+            span => compile_error!("`FiniteCompact` can't be derived for generic types");
+          }
+        }
+      }
+    }
+  }
+}
@@ -1,171 +1,482 @@
+use crate::codegen::bitpacked_codegen;
 use crate::codegen::bounds::add_trait_bounds;
-use crate::codegen::field_codegen::FieldCodegen;
-use proc_macro2::{Ident, Span, TokenStream};
+use crate::codegen::enum_codegen::EnumCodegen;
+use crate::codegen::idents;
+use crate::codegen::union_codegen::{ArmInfo, UnionCodegen};
+use crate::container::{Container, ContainerData};
+use proc_macro2::{Ident, Literal, TokenStream};
 use quote::quote;
-use syn::Data;
 
 use self::enum_builder::EnumBuilder;
 pub use self::error::*;
 
-type ImplResult<T> = Result<T, DeriveFiniteEncoding>;
+type ImplResult<T> = Result<T, DeriveFiniteDecoding>;
 
 pub fn impl_derive(ast: &syn::DeriveInput) -> ImplResult<TokenStream> {
-  let name = &ast.ident;
+  let container = Container::from_derive_input(ast)?;
+  impl_container(&container)
+}
+
+pub fn impl_container(container: &Container) -> ImplResult<TokenStream> {
+  let name = container.ident;
+  let krate = &container.krate;
+  let option = idents::option_path();
 
-  let bound = syn::parse_str("finite_repr::FiniteDecoding")
-    .map_err(|_| DeriveFiniteEncoding::CouldNotAddTraitBounds(ast.ident.span()))?;
+  let bound = syn::parse2(quote! { #krate::FiniteDecoding })
+    .map_err(|_| DeriveFiniteDecoding::CouldNotAddTraitBounds(name.span()))?;
 
-  let generics = add_trait_bounds(&ast.generics, &bound);
+  let generics = add_trait_bounds(container.generics, &bound);
   let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
 
-  let generic_param = Ident::new("T", Span::call_site());
-  let value_param = Ident::new("value", Span::call_site());
-  let body = generate_body(&ast, &value_param, &generic_param)?;
+  let generic_param = idents::generic_param();
+  let value_param = idents::local("value");
+
+  if let ContainerData::Union(codegen) = &container.data {
+    let body = generate_union_body(codegen, &value_param, &generic_param, krate);
+
+    return Ok(quote! {
+      #[automatically_derived]
+      impl #impl_generics #krate::FiniteUnionDecoding for #name #ty_generics #where_clause {
+        fn from_finite_arm<#generic_param: #krate::FiniteValue>(
+          #value_param: #generic_param,
+        ) -> #option<(<Self as #krate::FiniteUnion>::Tag, Self)> {
+          #body
+        }
+      }
+    });
+  }
+
+  let body = generate_body(container, &value_param, &generic_param);
+
+  // The offset of each variant is a constant, but as it can
+  // depend on the type's generics it needs to live in an impl.
+  let offsets_gen = match &container.data {
+    ContainerData::Enum(codegen) if uses_offsets(container, codegen) => {
+      let offsets = codegen.offsets_expression(krate);
+      let len = codegen.variants().len() + 1;
+      let offsets_name = idents::offsets_const();
+      quote! {
+        #[automatically_derived]
+        impl #impl_generics #name #ty_generics #where_clause {
+          #[doc(hidden)]
+          const #offsets_name: [usize; #len] = #offsets;
+        }
+      }
+    }
+    _ => quote! {},
+  };
+
+  let fallback_gen = match &container.fallback {
+    Some(variant) => quote! {
+      #[automatically_derived]
+      impl #impl_generics #krate::FiniteFallback for #name #ty_generics #where_clause {
+        fn fallback() -> Self {
+          #name::#variant
+        }
+      }
+    },
+    None => quote! {},
+  };
+
+  let field_error_gen = match generate_field_error_body(container, &value_param, &generic_param) {
+    Some(body) => quote! {
+      fn field_error<#generic_param: #krate::FiniteValue>(
+        #value_param: #generic_param,
+      ) -> #krate::DecodeError {
+        #body
+      }
+    },
+    None => quote! {},
+  };
 
   let implementation_gen = quote! {
-    impl #impl_generics finite_repr::FiniteDecoding for #name #ty_generics #where_clause {
-      fn from_finite<#generic_param: finite_repr::FiniteValue>(#value_param: #generic_param) -> Option<Self> {
+    #[automatically_derived]
+    impl #impl_generics #krate::FiniteDecoding for #name #ty_generics #where_clause {
+      fn from_finite<#generic_param: #krate::FiniteValue>(#value_param: #generic_param) -> #option<Self> {
         #body
       }
+
+      #field_error_gen
     }
+
+    #offsets_gen
+    #fallback_gen
   };
 
   Ok(implementation_gen)
 }
 
-fn generate_body(
-  ast: &syn::DeriveInput,
-  value_param: &Ident,
-  generic_param: &Ident,
-) -> ImplResult<TokenStream> {
-  match &ast.data {
-    Data::Enum(data_enum) => {
-      let mut builder = EnumBuilder::new(&ast.ident, value_param, generic_param);
+/**
+ * Enums that aren't decoded by a table or a cast locate their
+ * variant by searching a constant array of offsets.
+ */
+fn uses_offsets(container: &Container, codegen: &EnumCodegen) -> bool {
+  !container.table && codegen.primitive_repr().is_none() && !codegen.variants().is_empty()
+}
+
+fn generate_body(container: &Container, value_param: &Ident, generic_param: &Ident) -> TokenStream {
+  let krate = &container.krate;
 
-      for variant in data_enum.variants.iter() {
-        builder.with_variant(variant);
+  match &container.data {
+    _ if container.table => {
+      let name = container.ident;
+      let number = idents::local("number");
+      let table = idents::local("table");
+
+      // Borrowing the const as `'static` promotes it, rather
+      // than copying the whole table onto the stack.
+      quote! {
+        let #number = #generic_param::into_usize(#value_param)?;
+        let #table: &'static [#name] = &#name::ALL;
+        return #table.get(#number).copied();
       }
+    }
+    ContainerData::Enum(codegen) if codegen.primitive_repr().is_some() => {
+      let option = idents::option_path();
+      let number = idents::local("number");
+      let arms = codegen
+        .variants()
+        .iter()
+        .enumerate()
+        .map(|(index, variant)| {
+          let index = Literal::usize_unsuffixed(index);
+          let variant_name = &variant.ident;
+          quote! { #index => #option::Some(Self::#variant_name), }
+        });
 
-      Ok(builder.get_expression())
+      // The variants are contiguous, so this match is
+      // compiled to a bounds check & a jump table.
+      quote! {
+        let #number = #generic_param::into_usize(#value_param)?;
+        return match #number {
+          #(#arms)*
+          _ => #option::None,
+        };
+      }
     }
-    Data::Struct(struct_data) => {
-      let codegen = FieldCodegen::from_struct(&struct_data);
-      let type_name = &ast.ident;
+    ContainerData::Enum(codegen) if codegen.variants().is_empty() => {
+      let option = idents::option_path();
+      quote! { return #option::None; }
+    }
+    ContainerData::Enum(codegen) => {
+      let mut builder = EnumBuilder::new(container.ident, value_param, generic_param, krate);
+
+      for index in 0..codegen.variants().len() {
+        builder.with_variant(codegen, index);
+      }
+
+      builder.get_expression()
+    }
+    ContainerData::Struct(codegen) | ContainerData::Bitpacked(codegen) => {
+      let type_name = container.ident;
+      let option = idents::option_path();
       let value = quote! { #value_param };
-      let (constructor_stmts, constructor_arguments, condition) =
-        codegen.branch_construction(&value, generic_param);
+      let bitpacked = matches!(container.data, ContainerData::Bitpacked(_));
+      let (constructor_stmts, constructor_arguments, condition) = match bitpacked {
+        true => bitpacked_codegen::branch_construction(codegen, &value, generic_param, krate),
+        false => codegen.branch_construction(&value, generic_param, krate),
+      };
 
       let condition = match condition {
         Some(condition) => condition,
         None => {
-          let ceil_size = codegen.calc_size();
-          quote! { #value_param < #generic_param::from_usize(#ceil_size)? }
+          let ceil_size = match bitpacked {
+            true => bitpacked_codegen::size_expression(codegen, krate),
+            false => codegen.calc_size(krate),
+          };
+          quote! {
+            (#ceil_size) > #generic_param::MAX_USIZE
+              || #value_param < #generic_param::constant(#ceil_size)
+          }
         }
       };
 
-      Ok(quote! {
+      quote! {
         if #condition {
           #constructor_stmts
-          return Some(#type_name #constructor_arguments);
+          return #option::Some(#type_name #constructor_arguments);
         }
+        return #option::None;
+      }
+    }
+    ContainerData::Discriminant(codegen) => {
+      let option = idents::option_path();
+      let number = idents::local("number");
+      let mut body = quote! {};
+
+      // Numbers between discriminants are rejected by
+      // falling through every comparison.
+      for (index, variant) in codegen.variants().iter().enumerate() {
+        let variant_name = &variant.ident;
+        let discriminant = codegen.discriminant_expression(index);
+        body = quote! {
+          #body
+          if #number == #discriminant {
+            return #option::Some(Self::#variant_name);
+          }
+        };
+      }
+
+      quote! {
+        let #number = #generic_param::into_usize(#value_param)?;
+        #body
+        return #option::None;
+      }
+    }
+    ContainerData::Union(_) => unreachable!("unions implement `FiniteUnionDecoding`"),
+  }
+}
+
+/**
+ * Finds the field rejecting a number that's one of the type's
+ * representations, for types with fields that aren't decoded
+ * by a table. Other types only reject such numbers themselves.
+ */
+fn generate_field_error_body(
+  container: &Container,
+  value_param: &Ident,
+  generic_param: &Ident,
+) -> Option<TokenStream> {
+  let krate = &container.krate;
+  let value = quote! { #value_param };
+
+  match &container.data {
+    _ if container.table => None,
+    ContainerData::Struct(codegen) if !codegen.fields().is_empty() => {
+      Some(codegen.branch_error(&value, generic_param, krate))
+    }
+    ContainerData::Bitpacked(codegen) if !codegen.fields().is_empty() => Some(
+      bitpacked_codegen::branch_error(codegen, &value, generic_param, krate),
+    ),
+    ContainerData::Enum(codegen) if uses_offsets(container, codegen) => {
+      let offsets = idents::offsets_const();
+      let number = idents::local("number");
+      let offset = idents::local("offset");
+      let variant = idents::local("variant");
+      let variant_value = idents::local("variant_value");
+
+      let arms: Vec<_> = codegen
+        .variants()
+        .iter()
+        .enumerate()
+        .filter(|(_, variant)| !variant.repr.fields().is_empty())
+        .map(|(index, variant)| {
+          let literal = Literal::usize_unsuffixed(index);
+          let offset_value = match codegen.is_other(index) {
+            true => quote! { #value_param },
+            false => quote! { #value_param - #generic_param::constant(Self::#offsets[#literal]) },
+          };
+          let error = variant
+            .repr
+            .branch_error(&quote! { #variant_value }, generic_param, krate);
+
+          quote! {
+            #literal => {
+              let #variant_value = #offset_value;
+              #error
+            }
+          }
+        })
+        .collect();
+
+      if arms.is_empty() {
         return None;
+      }
+
+      Some(quote! {
+        let #number = match #generic_param::into_usize(#value_param) {
+          ::core::option::Option::Some(#number) => #number,
+          ::core::option::Option::None => return #krate::DecodeError::Invalid,
+        };
+        let #variant = Self::#offsets.partition_point(|#offset| *#offset <= #number) - 1;
+        match #variant {
+          #(#arms)*
+          _ => {}
+        }
+        return #krate::DecodeError::Invalid;
       })
     }
-    Data::Union(u) => Err(DeriveFiniteEncoding::UnionNotSupported(u.union_token.span)),
+    _ => None,
+  }
+}
+
+/**
+ * Decodes each arm as if it were the variant of an enum,
+ * pairing it with the tag that identifies it.
+ */
+fn generate_union_body(
+  codegen: &UnionCodegen,
+  value_param: &Ident,
+  generic_param: &Ident,
+  krate: &TokenStream,
+) -> TokenStream {
+  let option = idents::option_path();
+  let arm_value = idents::local("arm");
+  let mut body = quote! {};
+
+  for (index, arm) in codegen.arms().iter().enumerate() {
+    let floor_size = codegen.floor_expression(index, krate);
+    let ceil_size = codegen.ceil_expression(index, krate);
+    let ArmInfo { ident, ty, tag } = arm;
+
+    // Reaching an arm means the value is no less than the
+    // arm's floor, so the floor fits in the generic type.
+    body = quote! {
+      #body
+      if (#ceil_size) > #generic_param::MAX_USIZE
+        || #value_param < #generic_param::constant(#ceil_size)
+      {
+        let #arm_value = <#ty as #krate::FiniteDecoding>::from_finite(
+          #value_param - #generic_param::constant(#floor_size),
+        )?;
+        return #option::Some(((#tag), Self { #ident: #arm_value }));
+      }
+    };
+  }
+
+  quote! {
+    #body
+    return #option::None;
   }
 }
 
 mod enum_builder {
   use crate::codegen::enum_codegen::EnumCodegen;
-  use proc_macro2::{Ident, TokenStream};
+  use crate::codegen::idents;
+  use proc_macro2::{Ident, Literal, TokenStream};
   use quote::quote;
-  use syn::Variant;
 
+  /**
+   * Decodes an enum by binary searching the offsets of its
+   * variants for the one containing the number, then matching
+   * on the index of that variant.
+   */
   pub struct EnumBuilder<'a> {
-    body: TokenStream,
+    arms: TokenStream,
+    variant_count: usize,
     value_idenfier: &'a Ident,
     generic_param: &'a Ident,
-    codegen: EnumCodegen,
+    krate: &'a TokenStream,
     type_name: &'a Ident,
   }
 
   impl<'a> EnumBuilder<'a> {
-    pub fn new(type_name: &'a Ident, value_idenfier: &'a Ident, generic_param: &'a Ident) -> Self {
+    pub fn new(
+      type_name: &'a Ident,
+      value_idenfier: &'a Ident,
+      generic_param: &'a Ident,
+      krate: &'a TokenStream,
+    ) -> Self {
       EnumBuilder {
-        body: quote! {},
-        codegen: EnumCodegen::new(),
+        arms: quote! {},
+        variant_count: 0,
+        krate,
         type_name,
         generic_param,
         value_idenfier,
       }
     }
 
-    pub fn with_variant(&mut self, variant: &Variant) {
+    pub fn with_variant(&mut self, codegen: &EnumCodegen, index: usize) {
       let generic_param = &self.generic_param;
       let value_idenfier = &self.value_idenfier;
+      let variant = &codegen.variants()[index];
       let variant_name = &variant.ident;
+      let offsets = idents::offsets_const();
+      let variant_value = idents::local("variant_value");
+      let literal = Literal::usize_unsuffixed(index);
 
-      let floor_size = self.codegen.size_expresssion();
-      let variant = self.codegen.insert_variant(variant, None);
-
-      let value = quote! {
-        (#value_idenfier - #generic_param::from_usize(#floor_size)?)
-      };
-
-      let (constructor_stmts, constructor_arguments, condition) = variant
-        .repr
-        .branch_construction(&value, &self.generic_param);
+      let value = quote! { #variant_value };
+      let (constructor_stmts, constructor_arguments, condition) =
+        variant
+          .repr
+          .branch_construction(&value, self.generic_param, self.krate);
 
-      let body_so_far = &self.body;
+      let arms_so_far = &self.arms;
       let type_name = &self.type_name;
-      let condition = match condition {
-        Some(condition) => condition,
-        None => {
-          let ceil_size = self.codegen.size_expresssion();
-          quote! {
-            #value_idenfier < #generic_param::from_usize(#ceil_size)?
+      let option = idents::option_path();
+      let body = match condition {
+        Some(condition) => quote! {
+          if #condition {
+            #constructor_stmts
+            return #option::Some(#type_name::#variant_name #constructor_arguments);
           }
-        }
+        },
+        None => quote! {
+          #constructor_stmts
+          return #option::Some(#type_name::#variant_name #constructor_arguments);
+        },
       };
 
-      self.body = quote! {
-        #body_so_far
-        if #condition {
-          #constructor_stmts
-          return Some(#type_name::#variant_name #constructor_arguments);
+      // The catch-all holds the number itself, otherwise the
+      // offset is no greater than the value, so it fits.
+      let offset_value = match codegen.is_other(index) {
+        true => quote! { #value_idenfier },
+        false => quote! { #value_idenfier - #generic_param::constant(Self::#offsets[#literal]) },
+      };
+
+      self.variant_count += 1;
+      self.arms = quote! {
+        #arms_so_far
+        #literal => {
+          let #variant_value = #offset_value;
+          #body
         }
       };
     }
 
     pub fn get_expression(self) -> TokenStream {
-      let body = self.body;
+      let arms = self.arms;
+      let option = idents::option_path();
+      let generic_param = self.generic_param;
+      let value_idenfier = self.value_idenfier;
+      let offsets = idents::offsets_const();
+      let number = idents::local("number");
+      let offset = idents::local("offset");
+      let variant = idents::local("variant");
+      let last = Literal::usize_unsuffixed(self.variant_count);
+
+      // The last offset with a value no greater than the number
+      // is the variant, variants without any representations share
+      // an offset with the next variant so they're skipped over.
       quote! {
-        #body
-        return None;
+        let #number = #generic_param::into_usize(#value_idenfier)?;
+        if #number >= Self::#offsets[#last] {
+          return #option::None;
+        }
+        let #variant = Self::#offsets.partition_point(|#offset| *#offset <= #number) - 1;
+        match #variant {
+          #arms
+          _ => {}
+        }
+        return #option::None;
       }
     }
   }
 }
 
 mod error {
+  use crate::container::ContainerError;
   use crate::impl_error::CompilerError;
 
-  pub enum DeriveFiniteEncoding {
+  pub enum DeriveFiniteDecoding {
+    Container(ContainerError),
     CouldNotAddTraitBounds(proc_macro2::Span),
-    UnionNotSupported(proc_macro2::Span),
   }
 
-  impl CompilerError for DeriveFiniteEncoding {
+  impl From<ContainerError> for DeriveFiniteDecoding {
+    fn from(error: ContainerError) -> Self {
+      DeriveFiniteDecoding::Container(error)
+    }
+  }
+
+  impl CompilerError for DeriveFiniteDecoding {
     fn compile_error(&self) -> proc_macro2::TokenStream {
       match *self {
-        DeriveFiniteEncoding::CouldNotAddTraitBounds(span) => {
-          quote::quote_spanned! {
-            span => compile_error!("Tragic... Could not add a FiniteEncoding trait bound.")
-          }
-        }
-        DeriveFiniteEncoding::UnionNotSupported(span) => {
+        DeriveFiniteDecoding::Container(ref error) => error.compile_error(),
+        DeriveFiniteDecoding::CouldNotAddTraitBounds(span) => {
           quote::quote_spanned! {
-            span => compile_error!("union are not supported... yet(?)")
+            span => compile_error!("Tragic... Could not add a FiniteDecoding trait bound.");
           }
         }
       }
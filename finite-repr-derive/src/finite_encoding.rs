@@ -1,137 +1,325 @@
 use self::enum_builder::EnumBuilder;
+use crate::codegen::bitpacked_codegen;
 use crate::codegen::bounds::add_trait_bounds;
-use crate::codegen::field_codegen::FieldCodegen;
-use proc_macro2::{Ident, Span, TokenStream};
+use crate::codegen::enum_codegen::PrimitiveRepr;
+use crate::codegen::idents;
+use crate::codegen::union_codegen::{ArmInfo, UnionCodegen};
+use crate::container::{Container, ContainerData};
+use crate::finite_ord;
+use proc_macro2::{Ident, Literal, TokenStream};
 use quote::quote;
-use syn::Data;
 
 pub use error::*;
 
 type ImplResult<T> = Result<T, DeriveFiniteEncoding>;
 
 pub fn impl_derive(ast: &syn::DeriveInput) -> ImplResult<TokenStream> {
-  let name = &ast.ident;
+  let container = Container::from_derive_input(ast)?;
+  impl_container(&container)
+}
+
+pub fn impl_container(container: &Container) -> ImplResult<TokenStream> {
+  let name = container.ident;
+  let krate = &container.krate;
+  let option = idents::option_path();
+
+  let bound = syn::parse2(quote! { #krate::FiniteEncoding })
+    .map_err(|_| DeriveFiniteEncoding::CouldNotAddTraitBounds(name.span()))?;
+
+  let mut generics = add_trait_bounds(container.generics, &bound);
 
-  let bound = syn::parse_str("finite_repr::FiniteEncoding")
-    .map_err(|_| DeriveFiniteEncoding::CouldNotAddTraitBounds(ast.ident.span()))?;
+  // Encoding reads the arm each tag identifies, which is only
+  // sound when the type promises its tags are correct.
+  if container.holds_union() {
+    let (_, ty_generics, _) = container.generics.split_for_impl();
+    let predicate = syn::parse2(quote! { #name #ty_generics: #krate::FiniteTagged })
+      .map_err(|_| DeriveFiniteEncoding::CouldNotAddTraitBounds(name.span()))?;
+    generics.make_where_clause().predicates.push(predicate);
+  }
 
-  let generics = add_trait_bounds(&ast.generics, &bound);
   let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
 
-  let generic_param = Ident::new("T", Span::call_site());
-  let body = generate_body(&ast, &generic_param)?;
+  let generic_param = idents::generic_param();
+
+  if let ContainerData::Union(codegen) = &container.data {
+    let tag_param = idents::local("tag");
+    let body = generate_union_body(codegen, &tag_param, &generic_param, krate);
+
+    return Ok(quote! {
+      #[automatically_derived]
+      impl #impl_generics #krate::FiniteUnionEncoding for #name #ty_generics #where_clause {
+        unsafe fn into_finite_arm<#generic_param: #krate::FiniteValue>(
+          &self,
+          #tag_param: &<Self as #krate::FiniteUnion>::Tag,
+        ) -> #option<#generic_param> {
+          #body
+        }
+      }
+    });
+  }
+
+  let body = generate_body(container, &generic_param);
+
+  // Compares field by field like `FiniteOrd`, types holding a
+  // tagged union can't be & fall back to encoding both values.
+  let other = idents::local("other");
+  let cmp_gen = match finite_ord::generate_body(container, &other) {
+    Ok(cmp_body) => quote! {
+      fn finite_cmp(&self, #other: &Self) -> ::core::cmp::Ordering {
+        #cmp_body
+      }
+    },
+    Err(_) => quote! {},
+  };
 
   let implementation_gen = quote! {
-    impl #impl_generics finite_repr::FiniteEncoding for #name #ty_generics #where_clause {
-      fn into_finite<#generic_param: finite_repr::FiniteValue>(&self) -> Option<#generic_param> {
+    #[automatically_derived]
+    impl #impl_generics #krate::FiniteEncoding for #name #ty_generics #where_clause {
+      fn into_finite<#generic_param: #krate::FiniteValue>(&self) -> #option<#generic_param> {
         #body
       }
+
+      #cmp_gen
     }
   };
 
   Ok(implementation_gen)
 }
 
-fn generate_body(ast: &syn::DeriveInput, generic_param: &Ident) -> ImplResult<TokenStream> {
-  match &ast.data {
-    Data::Enum(data_enum) => {
-      let mut builder = EnumBuilder::new(&ast.ident, &generic_param);
+fn generate_body(container: &Container, generic_param: &Ident) -> TokenStream {
+  let krate = &container.krate;
 
-      for variant in data_enum.variants.iter() {
-        builder.with_variant(variant);
+  match &container.data {
+    _ if container.table => {
+      let name = container.ident;
+      quote! {
+        return #generic_param::from_usize(#name::index(self));
       }
+    }
+    ContainerData::Enum(codegen) if codegen.primitive_repr().is_some() => {
+      let PrimitiveRepr { ty, offset } = codegen.primitive_repr().unwrap();
+      let number = idents::local("number");
+
+      let encoded = match offset {
+        0 => quote! { #number },
+        offset => {
+          let offset = Literal::u64_unsuffixed(*offset);
+          quote! { #number - #offset }
+        }
+      };
 
-      Ok(builder.get_expression())
+      // A fieldless enum with a primitive repr is laid out as
+      // its discriminant, so it can be read without branching.
+      quote! {
+        let #number = unsafe { *(self as *const Self as *const ::core::primitive::#ty) } as usize;
+        return #generic_param::from_usize(#encoded);
+      }
     }
-    Data::Struct(struct_data) => {
-      let codegen = FieldCodegen::from_struct(&struct_data);
-      let type_name = &ast.ident;
+    ContainerData::Enum(codegen) => {
+      let mut builder = EnumBuilder::new(container.ident, generic_param, krate);
+
+      for index in 0..codegen.variants().len() {
+        builder.with_variant(codegen, index);
+      }
+
+      builder.get_expression()
+    }
+    ContainerData::Struct(codegen) | ContainerData::Bitpacked(codegen) => {
+      let type_name = container.ident;
+      let option = idents::option_path();
       let destructor = codegen.field_destructor();
-      let (branch_stmts, branch_size) = codegen.branch_size(generic_param);
+      let (branch_stmts, branch_size) = match &container.data {
+        ContainerData::Bitpacked(_) => {
+          bitpacked_codegen::branch_size(codegen, generic_param, krate)
+        }
+        _ => codegen.branch_size(generic_param, krate),
+      };
 
-      Ok(quote! {
+      let number = idents::local("number");
+
+      quote! {
         let #type_name #destructor = self;
         #branch_stmts
-        return Some(#branch_size);
-      })
+        let #number = #branch_size;
+        return #option::Some(#number);
+      }
     }
-    Data::Union(u) => Err(DeriveFiniteEncoding::UnionNotSupported(u.union_token.span)),
+    ContainerData::Discriminant(codegen) => {
+      let option = idents::option_path();
+      let mut body = quote! {};
+
+      for (index, variant) in codegen.variants().iter().enumerate() {
+        let variant_name = &variant.ident;
+        let discriminant = codegen.discriminant_expression(index);
+        body = quote! {
+          #body
+          if let Self::#variant_name = self {
+            return #generic_param::from_usize(#discriminant);
+          }
+        };
+      }
+
+      quote! {
+        #body
+        return #option::None;
+      }
+    }
+    ContainerData::Union(_) => unreachable!("unions implement `FiniteUnionEncoding`"),
+  }
+}
+
+/**
+ * Reads the arm identified by the tag, the caller of the
+ * generated method promises the tag is correct.
+ */
+fn generate_union_body(
+  codegen: &UnionCodegen,
+  tag_param: &Ident,
+  generic_param: &Ident,
+  krate: &TokenStream,
+) -> TokenStream {
+  let option = idents::option_path();
+  let number = idents::local("number");
+  let mut body = quote! {};
+
+  for (index, arm) in codegen.arms().iter().enumerate() {
+    let floor_size = codegen.floor_expression(index, krate);
+    let ArmInfo { ident, ty, tag } = arm;
+
+    body = quote! {
+      #body
+      if *#tag_param == (#tag) {
+        if (#floor_size) > #generic_param::MAX_USIZE {
+          return #option::None;
+        }
+        let #number = unsafe {
+          <#ty as #krate::FiniteEncoding>::into_finite::<#generic_param>(&self.#ident)?
+        };
+        return #krate::FiniteValue::checked_add(#generic_param::constant(#floor_size), #number);
+      }
+    };
+  }
+
+  quote! {
+    #body
+    return #option::None;
   }
 }
 
 mod enum_builder {
   use crate::codegen::enum_codegen::EnumCodegen;
+  use crate::codegen::idents;
   use proc_macro2::{Ident, TokenStream};
   use quote::quote;
-  use syn::Variant;
 
   pub struct EnumBuilder<'a> {
     body: TokenStream,
     generic_param: &'a Ident,
-    codegen: EnumCodegen,
+    krate: &'a TokenStream,
     type_name: &'a Ident,
   }
 
   impl<'a> EnumBuilder<'a> {
-    pub fn new(type_name: &'a Ident, generic_param: &'a Ident) -> Self {
+    pub fn new(type_name: &'a Ident, generic_param: &'a Ident, krate: &'a TokenStream) -> Self {
       EnumBuilder {
         body: quote! {},
         generic_param,
-        codegen: EnumCodegen::new(),
+        krate,
         type_name,
       }
     }
 
-    pub fn with_variant(&mut self, variant: &Variant) {
+    pub fn with_variant(&mut self, codegen: &EnumCodegen, index: usize) {
       let body_so_far = &self.body;
       let type_name = &self.type_name;
       let generic_param = &self.generic_param;
+      let variant = &codegen.variants()[index];
       let variant_name = &variant.ident;
 
-      let base_size = self.codegen.size_expresssion();
-      let variant = self.codegen.insert_variant(variant, None);
       let destructor = variant.repr.field_destructor();
-      let (branch_stmts, branch_size) = variant.repr.branch_size(generic_param);
+      let (branch_stmts, branch_size) = variant.repr.branch_size(generic_param, self.krate);
+      let option = idents::option_path();
+      let number = idents::local("number");
+
+      // The first variant has no offset, the offset of every
+      // other variant is a constant checked once to fit.
+      let encoded = match index {
+        // The catch-all is its field's number, which is only
+        // valid beyond the numbers of the other variants.
+        _ if codegen.is_other(index) => {
+          let base_size = codegen.floor_expression(index, self.krate);
+          quote! {
+            if (#base_size) > #generic_param::MAX_USIZE {
+              return #option::None;
+            }
+            #branch_stmts
+            let #number = #branch_size;
+            if #number < #generic_param::constant(#base_size) {
+              return #option::None;
+            }
+            return #option::Some(#number);
+          }
+        }
+        0 => quote! {
+          #branch_stmts
+          let #number = #branch_size;
+          return #option::Some(#number);
+        },
+        _ => {
+          let base_size = codegen.floor_expression(index, self.krate);
+          let krate = self.krate;
+          quote! {
+            if (#base_size) > #generic_param::MAX_USIZE {
+              return #option::None;
+            }
+            #branch_stmts
+            let #number = #branch_size;
+            return #krate::FiniteValue::checked_add(#generic_param::constant(#base_size), #number);
+          }
+        }
+      };
 
       self.body = quote! {
         #body_so_far
         if let #type_name::#variant_name #destructor = self {
-          let base = #generic_param::from_usize(#base_size)?;
-          #branch_stmts
-          return Some(base + #branch_size);
+          #encoded
         }
       };
     }
 
     pub fn get_expression(self) -> TokenStream {
       let body = self.body;
+      let option = idents::option_path();
       quote! {
         #body
-        return None;
+        return #option::None;
       }
     }
   }
 }
 
 mod error {
+  use crate::container::ContainerError;
   use crate::impl_error::CompilerError;
 
   pub enum DeriveFiniteEncoding {
+    Container(ContainerError),
     CouldNotAddTraitBounds(proc_macro2::Span),
-    UnionNotSupported(proc_macro2::Span),
+  }
+
+  impl From<ContainerError> for DeriveFiniteEncoding {
+    fn from(error: ContainerError) -> Self {
+      DeriveFiniteEncoding::Container(error)
+    }
   }
 
   impl CompilerError for DeriveFiniteEncoding {
     fn compile_error(&self) -> proc_macro2::TokenStream {
       match *self {
+        DeriveFiniteEncoding::Container(ref error) => error.compile_error(),
         DeriveFiniteEncoding::CouldNotAddTraitBounds(span) => {
           quote::quote_spanned! {
-            span => compile_error!("Tragic... Could not add a FiniteEncoding trait bound.")
-          }
-        }
-        DeriveFiniteEncoding::UnionNotSupported(span) => {
-          quote::quote_spanned! {
-            span => compile_error!("union are not supported... yet(?)")
+            span => compile_error!("Tragic... Could not add a FiniteEncoding trait bound.");
           }
         }
       }
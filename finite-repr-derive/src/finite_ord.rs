@@ -0,0 +1,239 @@
+use crate::codegen::bounds::add_trait_bounds;
+use crate::codegen::field_codegen::{FieldCodegen, FieldRole};
+use crate::codegen::idents;
+use crate::container::{Container, ContainerData};
+use proc_macro2::{Ident, Literal, TokenStream};
+use quote::{quote, quote_spanned};
+use syn::spanned::Spanned;
+
+pub use error::*;
+
+type ImplResult<T> = Result<T, DeriveFiniteOrd>;
+
+pub fn impl_derive(ast: &syn::DeriveInput) -> ImplResult<TokenStream> {
+  let container = Container::from_derive_input(ast)?;
+  impl_container(&container)
+}
+
+/**
+ * Implements `Ord` & `PartialOrd` so values compare the same
+ * as their encodings, without encoding either as a whole. Fields
+ * are compared from the most significant to the least, each with
+ * `FiniteEncoding::finite_cmp`, since field types such as options
+ * & tuples order differently with `Ord`.
+ */
+pub fn impl_container(container: &Container) -> ImplResult<TokenStream> {
+  let name = container.ident;
+  let krate = &container.krate;
+  let option = idents::option_path();
+  let ordering = ordering_path();
+
+  // Fields are compared by their encodings, `Eq` is only
+  // needed as a supertrait of `Ord`.
+  let bound = syn::parse2(quote! { #krate::FiniteEncoding })
+    .map_err(|_| DeriveFiniteOrd::CouldNotAddTraitBounds(name.span()))?;
+  let eq_bound = syn::parse2(quote! { ::core::cmp::Eq })
+    .map_err(|_| DeriveFiniteOrd::CouldNotAddTraitBounds(name.span()))?;
+
+  let generics = add_trait_bounds(container.generics, &bound);
+  let generics = add_trait_bounds(&generics, &eq_bound);
+  let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+  let other = idents::local("other");
+  let body = generate_body(container, &other)?;
+
+  Ok(quote! {
+    #[automatically_derived]
+    impl #impl_generics ::core::cmp::Ord for #name #ty_generics #where_clause {
+      fn cmp(&self, #other: &Self) -> #ordering {
+        #body
+      }
+    }
+
+    #[automatically_derived]
+    impl #impl_generics ::core::cmp::PartialOrd for #name #ty_generics #where_clause {
+      fn partial_cmp(&self, #other: &Self) -> #option<#ordering> {
+        #option::Some(::core::cmp::Ord::cmp(self, #other))
+      }
+    }
+  })
+}
+
+/**
+ * The body of a comparison between `self` & `other`, shared with
+ * the `finite_cmp` of a derived `FiniteEncoding`.
+ */
+pub fn generate_body(container: &Container, other: &Ident) -> ImplResult<TokenStream> {
+  let type_name = container.ident;
+  let ordering = ordering_path();
+
+  match &container.data {
+    ContainerData::Struct(codegen) | ContainerData::Bitpacked(codegen) => {
+      check_fields(container, codegen)?;
+      let lhs = codegen.field_destructor_with(lhs_ident);
+      let rhs = codegen.field_destructor_with(rhs_ident);
+      let compare = compare_fields(codegen, &container.krate);
+
+      Ok(quote! {
+        let #type_name #lhs = self;
+        let #type_name #rhs = #other;
+        #compare
+      })
+    }
+    ContainerData::Enum(codegen) if codegen.variants().is_empty() => Ok(quote! { match *self {} }),
+    ContainerData::Enum(codegen) | ContainerData::Discriminant(codegen) => {
+      for variant in codegen.variants() {
+        check_fields(container, &variant.repr)?;
+      }
+
+      // Variants are laid out in declaration order, or by
+      // their discriminant when they're encoded as one.
+      let discriminant = matches!(container.data, ContainerData::Discriminant(_));
+      let position = |value: &TokenStream| {
+        let arms = codegen
+          .variants()
+          .iter()
+          .enumerate()
+          .map(|(index, variant)| {
+            let variant_name = &variant.ident;
+            let position = match discriminant {
+              true => codegen.discriminant_expression(index),
+              false => {
+                let index = Literal::usize_unsuffixed(index);
+                quote! { #index }
+              }
+            };
+            quote! { #type_name::#variant_name { .. } => #position, }
+          });
+        quote! { match #value { #(#arms)* } }
+      };
+
+      let lhs_position = position(&quote! { self });
+      let rhs_position = position(&quote! { #other });
+      let lhs_variant = idents::local("lhs_variant");
+      let rhs_variant = idents::local("rhs_variant");
+
+      let arms = codegen.variants().iter().map(|variant| {
+        let variant_name = &variant.ident;
+        let lhs = variant.repr.field_destructor_with(lhs_ident);
+        let rhs = variant.repr.field_destructor_with(rhs_ident);
+        let compare = compare_fields(&variant.repr, &container.krate);
+        quote! {
+          (#type_name::#variant_name #lhs, #type_name::#variant_name #rhs) => { #compare }
+        }
+      });
+
+      // Values of different variants returned early, so the
+      // fallback is only reachable with several variants.
+      let fallback = match codegen.variants().len() {
+        1 => quote! {},
+        _ => quote! { _ => #ordering::Equal, },
+      };
+
+      Ok(quote! {
+        let #lhs_variant: usize = #lhs_position;
+        let #rhs_variant: usize = #rhs_position;
+        if #lhs_variant != #rhs_variant {
+          return ::core::cmp::Ord::cmp(&#lhs_variant, &#rhs_variant);
+        }
+        match (self, #other) {
+          #(#arms)*
+          #fallback
+        }
+      })
+    }
+    ContainerData::Union(_) => Err(DeriveFiniteOrd::UnsupportedUnion(type_name.span())),
+  }
+}
+
+/**
+ * A tagged union is ordered by its arm & the arm's value, which
+ * isn't something the fields holding it can be compared by.
+ */
+fn check_fields(container: &Container, codegen: &FieldCodegen) -> ImplResult<()> {
+  match codegen.fields().iter().find(|f| f.role != FieldRole::Value) {
+    Some(_) => Err(DeriveFiniteOrd::UnsupportedUnion(container.ident.span())),
+    None => Ok(()),
+  }
+}
+
+/**
+ * Compares the fields bound to `lhs_ident` & `rhs_ident`
+ * from the most significant to the least, returning at the
+ * first that differs. Fields only encode to compare when their
+ * type doesn't know its order.
+ */
+fn compare_fields(codegen: &FieldCodegen, krate: &TokenStream) -> TokenStream {
+  let ordering = ordering_path();
+  let result = idents::local("ordering");
+  let mut tokens = quote! {};
+
+  for index in codegen.significance() {
+    let ty = &codegen.fields()[index].ty;
+    let lhs = lhs_ident(index);
+    let rhs = rhs_ident(index);
+    let compare = quote_spanned! { ty.span() =>
+      <#ty as #krate::FiniteEncoding>::finite_cmp(#lhs, #rhs)
+    };
+
+    tokens = quote! {
+      #tokens
+      match #compare {
+        #ordering::Equal => {}
+        #result => return #result,
+      }
+    };
+  }
+
+  quote! {
+    #tokens
+    #ordering::Equal
+  }
+}
+
+fn lhs_ident(index: usize) -> Ident {
+  idents::local(&format!("lhs_{}", index))
+}
+
+fn rhs_ident(index: usize) -> Ident {
+  idents::local(&format!("rhs_{}", index))
+}
+
+fn ordering_path() -> TokenStream {
+  quote! { ::core::cmp::Ordering }
+}
+
+mod error {
+  use crate::container::ContainerError;
+  use crate::impl_error::CompilerError;
+
+  pub enum DeriveFiniteOrd {
+    Container(ContainerError),
+    CouldNotAddTraitBounds(proc_macro2::Span),
+    UnsupportedUnion(proc_macro2::Span),
+  }
+
+  impl From<ContainerError> for DeriveFiniteOrd {
+    fn from(error: ContainerError) -> Self {
+      DeriveFiniteOrd::Container(error)
+    }
+  }
+
+  impl CompilerError for DeriveFiniteOrd {
+    fn compile_error(&self) -> proc_macro2::TokenStream {
+      match *self {
+        DeriveFiniteOrd::Container(ref error) => error.compile_error(),
+        DeriveFiniteOrd::CouldNotAddTraitBounds(span) => {
+          quote::quote_spanned! {
+            span => compile_error!("Tragic... Could not add an Ord trait bound.");
+          }
+        }
+        DeriveFiniteOrd::UnsupportedUnion(span) => {
+          quote::quote_spanned! {
+            span => compile_error!("`FiniteOrd` isn't supported on tagged unions or types holding them");
+          }
+        }
+      }
+    }
+  }
+}
@@ -1,73 +1,165 @@
+use crate::codegen::bitpacked_codegen;
 use crate::codegen::bounds::add_trait_bounds;
-use crate::codegen::enum_codegen::EnumCodegen;
-use crate::codegen::field_codegen::FieldCodegen;
-use proc_macro2::TokenStream;
+use crate::container::{Container, ContainerData};
+use crate::finite_table;
+use proc_macro2::{Literal, TokenStream};
 use quote::quote;
-use syn::{Data, Ident};
 
 pub use error::*;
 
 type ImplResult<T> = Result<T, DeriveFiniteRepr>;
 
 pub fn impl_derive(ast: &syn::DeriveInput) -> ImplResult<TokenStream> {
-  let name = &ast.ident;
+  let container = Container::from_derive_input(ast)?;
+  impl_container(&container)
+}
+
+pub fn impl_container(container: &Container) -> ImplResult<TokenStream> {
+  let name = container.ident;
+  let krate = &container.krate;
 
-  let bound = syn::parse_str("finite_repr::FiniteRepr")
-    .map_err(|_| DeriveFiniteRepr::CouldNotAddTraitBounds(ast.ident.span()))?;
+  let bound = syn::parse2(quote! { #krate::FiniteRepr })
+    .map_err(|_| DeriveFiniteRepr::CouldNotAddTraitBounds(name.span()))?;
 
-  let generics = add_trait_bounds(&ast.generics, &bound);
+  let generics = add_trait_bounds(container.generics, &bound);
   let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
-  let reprs = count_reprs(&ast.data)?;
+  let reprs = count_reprs(container);
 
-  let implementation_gen = quote! {
-    impl #impl_generics finite_repr::FiniteRepr for #name #ty_generics #where_clause {
-      const REPRS: usize = #reprs;
+  // A union also needs the type of its tag, which is shared
+  // by its encoding & decoding.
+  let extra_gen = match &container.data {
+    ContainerData::Union(codegen) => {
+      let tag_type = codegen.tag_type();
+      quote! {
+        #[automatically_derived]
+        impl #impl_generics #krate::FiniteUnion for #name #ty_generics #where_clause {
+          type Tag = #tag_type;
+        }
+      }
+    }
+    // Associated consts are only evaluated when used, this forces
+    // a negative discriminant to be reported by the derive.
+    ContainerData::Discriminant(_) => quote! {
+      const _: usize = <#name #ty_generics as #krate::FiniteRepr>::REPRS;
+    },
+    ContainerData::Bitpacked(codegen) => {
+      let wasted_bits = bitpacked_codegen::wasted_bits_expression(codegen, krate);
+      quote! {
+        #[automatically_derived]
+        impl #impl_generics #name #ty_generics #where_clause {
+          /**
+           * The number of bits this layout uses beyond the
+           * fewest needed to encode the same fields.
+           */
+          pub const WASTED_BITS: u32 = #wasted_bits;
+        }
+      }
     }
+    _ => quote! {},
   };
 
-  Ok(implementation_gen)
-}
-
-fn count_reprs(data: &Data) -> ImplResult<TokenStream> {
-  match data {
-    Data::Enum(d) => {
-      let mut reprs = EnumCodegen::new();
-      let mut parent: Option<Ident> = None;
+  let table_gen = match container.table {
+    true => finite_table::impl_container(container),
+    false => quote! {},
+  };
 
-      for variant in d.variants.iter() {
-        reprs.insert_variant(&variant, parent.clone());
-        parent = Some(variant.ident.clone());
+  let fits_gen = match &container.fits {
+    Some(target) => {
+      let message = format!(
+        "`{}` has more representations than `{}` can hold",
+        name,
+        quote! { #target }
+      );
+      quote! {
+        const _: () = #krate::FitsIn::<
+          { <#name as #krate::FiniteRepr>::REPRS },
+          { <#target as #krate::FiniteTarget>::CAPACITY },
+        >::assert(#message);
       }
+    }
+    None => quote! {},
+  };
 
-      Ok(reprs.size_expresssion())
+  // A reserved type has a fixed number of representations, those
+  // it doesn't use yet are reserved for values added later.
+  let reprs_gen = match container.reserve {
+    Some(reserve) => {
+      let reserve = Literal::usize_unsuffixed(reserve);
+      let message = format!("`{}` has more representations than it reserves", name);
+      quote! {
+        const REPRS: usize = {
+          if #reprs > #reserve {
+            ::core::panic!(#message);
+          }
+          #reserve
+        };
+        const USED_REPRS: usize = #reprs;
+      }
     }
-    Data::Struct(s) => {
-      let reprs = FieldCodegen::from_struct(&s);
-      Ok(reprs.calc_size())
+    None => quote! {
+      const REPRS: usize = #reprs;
+    },
+  };
+
+  // Associated consts are only evaluated when used, this reports
+  // a type outgrowing its reservation, or a catch-all too small
+  // to hold anything, even if it's never encoded.
+  let has_other = matches!(&container.data, ContainerData::Enum(codegen) if codegen.has_other());
+  let reprs_check = match container.reserve.is_some() || has_other {
+    true if container.generics.params.is_empty() => quote! {
+      const _: usize = <#name as #krate::FiniteRepr>::REPRS;
+    },
+    _ => quote! {},
+  };
+
+  let implementation_gen = quote! {
+    #[automatically_derived]
+    impl #impl_generics #krate::FiniteRepr for #name #ty_generics #where_clause {
+      #reprs_gen
     }
-    Data::Union(u) => Err(DeriveFiniteRepr::UnionNotSupported(u.union_token.span)),
+
+    #reprs_check
+
+    #extra_gen
+    #table_gen
+    #fits_gen
+  };
+
+  Ok(implementation_gen)
+}
+
+fn count_reprs(container: &Container) -> TokenStream {
+  match &container.data {
+    ContainerData::Enum(reprs) => reprs.size_expression(&container.krate),
+    ContainerData::Struct(reprs) => reprs.calc_size(&container.krate),
+    ContainerData::Union(reprs) => reprs.size_expression(&container.krate),
+    ContainerData::Discriminant(reprs) => reprs.discriminant_span_expression(),
+    ContainerData::Bitpacked(reprs) => bitpacked_codegen::size_expression(reprs, &container.krate),
   }
 }
 
 mod error {
+  use crate::container::ContainerError;
   use crate::impl_error::CompilerError;
 
   pub enum DeriveFiniteRepr {
+    Container(ContainerError),
     CouldNotAddTraitBounds(proc_macro2::Span),
-    UnionNotSupported(proc_macro2::Span),
+  }
+
+  impl From<ContainerError> for DeriveFiniteRepr {
+    fn from(error: ContainerError) -> Self {
+      DeriveFiniteRepr::Container(error)
+    }
   }
 
   impl CompilerError for DeriveFiniteRepr {
     fn compile_error(&self) -> proc_macro2::TokenStream {
       match *self {
+        DeriveFiniteRepr::Container(ref error) => error.compile_error(),
         DeriveFiniteRepr::CouldNotAddTraitBounds(span) => {
           quote::quote_spanned! {
-            span => compile_error!("Tragic... Could not add a FiniteRepr trait bound.")
-          }
-        }
-        DeriveFiniteRepr::UnionNotSupported(span) => {
-          quote::quote_spanned! {
-            span => compile_error!("union are not supported... yet(?)")
+            span => compile_error!("Tragic... Could not add a FiniteRepr trait bound.");
           }
         }
       }
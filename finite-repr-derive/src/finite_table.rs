@@ -0,0 +1,162 @@
+use crate::codegen::idents;
+use crate::codegen::table_codegen::{construction_arguments, index_expression, reserve_checks};
+use crate::container::{Container, ContainerData};
+use proc_macro2::TokenStream;
+use quote::quote;
+
+/**
+ * Tables larger than this take too long to build at compile
+ * time & are too large to be worth keeping in the binary.
+ */
+const MAX_TABLE_REPRS: usize = 4096;
+
+/**
+ * Generates `const ALL`, listing every value in the order
+ * they're encoded, & `const fn index` which is the inverse.
+ */
+pub fn impl_container(container: &Container) -> TokenStream {
+  let name = container.ident;
+  let krate = &container.krate;
+  let reprs = quote! { <#name as #krate::FiniteRepr>::USED_REPRS };
+
+  let from_index = idents::local("from_index");
+  let index_param = idents::local("index");
+  let all = idents::local("all");
+  let (index_body, from_index_body) = generate_bodies(container, &index_param);
+
+  let all_gen = match &container.data {
+    ContainerData::Enum(codegen) if codegen.variants().is_empty() => quote! { [] },
+    _ => quote! {
+      {
+        let mut #all = [#name::#from_index(0); #reprs];
+        let mut #index_param = 1;
+        while #index_param < #reprs {
+          #all[#index_param] = #name::#from_index(#index_param);
+          #index_param += 1;
+        }
+        #all
+      }
+    },
+  };
+
+  let limit_message = format!(
+    "`#[finite(table)]` is only supported on types with at most {} representations",
+    MAX_TABLE_REPRS
+  );
+
+  let field_checks = match &container.data {
+    ContainerData::Struct(codegen) => reserve_checks(codegen, krate),
+    ContainerData::Enum(codegen) => {
+      let checks = codegen
+        .variants()
+        .iter()
+        .map(|variant| reserve_checks(&variant.repr, krate));
+      quote! { #(#checks)* }
+    }
+    _ => quote! {},
+  };
+
+  quote! {
+    const _: () = ::core::assert!(#reprs <= #MAX_TABLE_REPRS, #limit_message);
+    #field_checks
+
+    #[automatically_derived]
+    impl #name {
+      /**
+       * Every value of this type, in the order they're encoded.
+       */
+      pub const ALL: [#name; #reprs] = #all_gen;
+
+      /**
+       * The position of this value in `ALL`, which is also the
+       * number it's encoded as.
+       */
+      pub const fn index(&self) -> usize {
+        #index_body
+      }
+
+      const fn #from_index(#index_param: usize) -> Self {
+        #from_index_body
+      }
+    }
+  }
+}
+
+fn generate_bodies(
+  container: &Container,
+  index_param: &proc_macro2::Ident,
+) -> (TokenStream, TokenStream) {
+  let krate = &container.krate;
+  let value = quote! { #index_param };
+
+  match &container.data {
+    ContainerData::Struct(codegen) => {
+      let destructor = codegen.field_destructor();
+      let index = index_expression(codegen, krate);
+      let arguments = construction_arguments(codegen, &value, krate);
+
+      let index_body = match codegen.fields() {
+        [] => quote! { 0 },
+        _ => quote! {
+          let Self #destructor = self;
+          #index
+        },
+      };
+      (index_body, quote! { Self #arguments })
+    }
+    ContainerData::Enum(codegen) => {
+      let mut index_arms = quote! {};
+      let mut from_index_body = quote! {};
+      let offset = idents::local("offset");
+
+      for (index, variant) in codegen.variants().iter().enumerate() {
+        let variant_name = &variant.ident;
+        let destructor = variant.repr.field_destructor();
+        let field_index = index_expression(&variant.repr, krate);
+        let floor = codegen.floor_expression(index, krate);
+        let ceil = codegen.ceil_expression(index, krate);
+
+        // The first variant starts at zero, so the index needs
+        // no adjustment.
+        let (variant_index, variant_value) = match (index, variant.repr.fields()) {
+          (0, _) => (field_index, value.clone()),
+          (_, []) => (quote! { #floor }, quote! { #index_param - (#floor) }),
+          (_, _) => (
+            quote! { (#floor) + #field_index },
+            quote! { #index_param - (#floor) },
+          ),
+        };
+        let arguments = construction_arguments(&variant.repr, &quote! { #offset }, krate);
+
+        index_arms = quote! {
+          #index_arms
+          Self::#variant_name #destructor => #variant_index,
+        };
+        from_index_body = quote! {
+          #from_index_body
+          if #index_param < (#ceil) {
+            let #offset = #variant_value;
+            return Self::#variant_name #arguments;
+          }
+        };
+      }
+
+      let index_body = match codegen.variants() {
+        [] => quote! { match *self {} },
+        _ => quote! {
+          match self {
+            #index_arms
+          }
+        },
+      };
+      let from_index_body = quote! {
+        #from_index_body
+        ::core::panic!("index out of range")
+      };
+      (index_body, from_index_body)
+    }
+    ContainerData::Union(_) | ContainerData::Discriminant(_) | ContainerData::Bitpacked(_) => {
+      unreachable!("rejected by `Container::from_derive_input`")
+    }
+  }
+}
@@ -1,15 +1,32 @@
 extern crate proc_macro;
 
+mod attributes;
 mod codegen;
+mod container;
+mod finite;
+mod finite_compact;
 mod finite_decoding;
 mod finite_encoding;
+mod finite_ord;
 mod finite_repr;
+mod finite_table;
 mod impl_error;
 
 use impl_error::CompilerError;
 use proc_macro::TokenStream;
 
-#[proc_macro_derive(FiniteEncoding)]
+/**
+ * Derives `FiniteRepr`, `FiniteEncoding` & `FiniteDecoding` at once.
+ */
+#[proc_macro_derive(Finite, attributes(finite))]
+pub fn derive_finite(input: TokenStream) -> TokenStream {
+  match syn::parse(input) {
+    Ok(data) => flatten_stream(finite::impl_derive(&data)),
+    Err(e) => e.to_compile_error().into(),
+  }
+}
+
+#[proc_macro_derive(FiniteEncoding, attributes(finite))]
 pub fn derive_finite_encoding(input: TokenStream) -> TokenStream {
   match syn::parse(input) {
     Ok(data) => flatten_stream(finite_encoding::impl_derive(&data)),
@@ -17,7 +34,7 @@ pub fn derive_finite_encoding(input: TokenStream) -> TokenStream {
   }
 }
 
-#[proc_macro_derive(FiniteDecoding)]
+#[proc_macro_derive(FiniteDecoding, attributes(finite))]
 pub fn derive_finite_decoding(input: TokenStream) -> TokenStream {
   match syn::parse(input) {
     Ok(data) => flatten_stream(finite_decoding::impl_derive(&data)),
@@ -25,7 +42,7 @@ pub fn derive_finite_decoding(input: TokenStream) -> TokenStream {
   }
 }
 
-#[proc_macro_derive(FiniteRepr)]
+#[proc_macro_derive(FiniteRepr, attributes(finite))]
 pub fn derive_finite_repr(input: TokenStream) -> TokenStream {
   match syn::parse(input) {
     Ok(data) => flatten_stream(finite_repr::impl_derive(&data)),
@@ -33,6 +50,30 @@ pub fn derive_finite_repr(input: TokenStream) -> TokenStream {
   }
 }
 
+/**
+ * Derives `Ord` & `PartialOrd` so values compare the same way
+ * as the numbers they're encoded as.
+ */
+#[proc_macro_derive(FiniteOrd, attributes(finite))]
+pub fn derive_finite_ord(input: TokenStream) -> TokenStream {
+  match syn::parse(input) {
+    Ok(data) => flatten_stream(finite_ord::impl_derive(&data)),
+    Err(e) => e.to_compile_error().into(),
+  }
+}
+
+/**
+ * Derives `FiniteCompact`, encoding into the narrowest integer
+ * that holds every representation of the type.
+ */
+#[proc_macro_derive(FiniteCompact, attributes(finite))]
+pub fn derive_finite_compact(input: TokenStream) -> TokenStream {
+  match syn::parse(input) {
+    Ok(data) => flatten_stream(finite_compact::impl_derive(&data)),
+    Err(e) => e.to_compile_error().into(),
+  }
+}
+
 fn flatten_stream<E: CompilerError>(r: Result<proc_macro2::TokenStream, E>) -> TokenStream {
   match r {
     Ok(s) => s.into(),
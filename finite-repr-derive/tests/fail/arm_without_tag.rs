@@ -0,0 +1,11 @@
+use finite_repr::Finite;
+
+#[derive(Clone, Copy, Finite)]
+#[finite(tag = u8)]
+union Untagged {
+  #[finite(tag = 0)]
+  a: u8,
+  b: bool,
+}
+
+fn main() {}
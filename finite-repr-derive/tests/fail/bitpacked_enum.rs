@@ -0,0 +1,10 @@
+use finite_repr::Finite;
+
+#[derive(Finite)]
+#[finite(bitpacked)]
+enum Packet {
+  Empty,
+  Payload(u8),
+}
+
+fn main() {}
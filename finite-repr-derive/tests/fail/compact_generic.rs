@@ -0,0 +1,9 @@
+use finite_repr::{Finite, FiniteCompact};
+
+#[derive(Clone, Copy, Finite, FiniteCompact)]
+struct Pair<T> {
+  left: T,
+  right: T,
+}
+
+fn main() {}
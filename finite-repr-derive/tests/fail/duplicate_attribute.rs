@@ -0,0 +1,8 @@
+use finite_repr::Finite;
+
+#[derive(Finite)]
+#[finite(crate = "finite_repr")]
+#[finite(crate = "finite_repr")]
+struct Duplicated(bool);
+
+fn main() {}
@@ -0,0 +1,10 @@
+use finite_repr::Finite;
+
+#[derive(Finite)]
+enum Reading {
+  Empty,
+  #[finite(fallback)]
+  Value(u8),
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use finite_repr::Finite;
+
+#[derive(Finite)]
+struct OnField {
+  #[finite(crate = "finite_repr")]
+  a: bool,
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use finite_repr::Finite;
+
+#[derive(Finite)]
+struct HasUnsupportedField {
+  supported: bool,
+  unsupported: u64,
+}
+
+fn main() {}
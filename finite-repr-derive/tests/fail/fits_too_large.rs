@@ -0,0 +1,7 @@
+use finite_repr::Finite;
+
+#[derive(Clone, Copy, Finite)]
+#[finite(fits = "u8")]
+struct Pair(u8, bool);
+
+fn main() {}
@@ -0,0 +1,7 @@
+use finite_repr::Finite;
+
+#[derive(Finite)]
+#[finite(crate = "not a path")]
+struct InvalidPath(bool);
+
+fn main() {}
@@ -0,0 +1,7 @@
+use finite_repr::Finite;
+
+#[derive(Finite)]
+#[finite(order = "reverse")]
+struct Key(bool, u8);
+
+fn main() {}
@@ -0,0 +1,8 @@
+use finite_repr::{Finite, FiniteEncoding, JsSafeInt};
+
+#[derive(Finite)]
+struct Wide(u8, u8, u8, u8, u8, u8, u8);
+
+fn main() {
+  let _ = Wide(0, 0, 0, 0, 0, 0, 0).into_finite_target::<JsSafeInt>();
+}
@@ -0,0 +1,11 @@
+use finite_repr::Finite;
+
+#[derive(Finite)]
+#[finite(discriminant)]
+#[repr(i8)]
+enum Signed {
+  Negative = -1,
+  Positive = 1,
+}
+
+fn main() {}
@@ -0,0 +1,21 @@
+use finite_repr::{Finite, FiniteOrd, FiniteTagged};
+
+#[derive(Clone, Copy, Finite)]
+#[finite(tag = u8)]
+union Payload {
+  #[finite(tag = 0)]
+  flag: bool,
+  #[finite(tag = 1)]
+  byte: u8,
+}
+
+#[derive(Clone, Copy, Finite, FiniteOrd)]
+struct Message {
+  kind: u8,
+  #[finite(tag = kind)]
+  payload: Payload,
+}
+
+unsafe impl FiniteTagged for Message {}
+
+fn main() {}
@@ -0,0 +1,18 @@
+use finite_repr::Finite;
+
+#[derive(Finite)]
+enum Command {
+  Stop,
+  #[finite(other)]
+  Unknown(u32),
+  Start,
+}
+
+#[derive(Finite)]
+enum Reading {
+  Empty,
+  #[finite(other)]
+  Unknown,
+}
+
+fn main() {}
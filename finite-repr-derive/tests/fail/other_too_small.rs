@@ -0,0 +1,11 @@
+use finite_repr::Finite;
+
+#[derive(Clone, Copy, Finite)]
+enum Reading {
+  Low(u8),
+  High(u8),
+  #[finite(other)]
+  Unknown(u8),
+}
+
+fn main() {}
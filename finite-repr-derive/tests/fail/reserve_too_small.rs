@@ -0,0 +1,18 @@
+use finite_repr::Finite;
+
+#[derive(Clone, Copy, Finite)]
+#[finite(reserve = 3)]
+enum Colour {
+  Red,
+  Green,
+  Blue(bool),
+}
+
+#[derive(Clone, Copy, Finite)]
+#[finite(reserve = "many")]
+enum Shade {
+  Light,
+  Dark,
+}
+
+fn main() {}
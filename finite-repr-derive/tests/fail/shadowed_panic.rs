@@ -0,0 +1,34 @@
+use finite_repr::Finite;
+
+// A local `panic!` that does nothing mustn't disable the checks
+// the derives make at compile time.
+#[allow(unused_macros)]
+macro_rules! panic {
+  ($($tokens:tt)*) => {};
+}
+
+#[derive(Clone, Copy, Finite)]
+#[finite(reserve = 1)]
+enum Reserved {
+  A,
+  B,
+  C,
+}
+
+#[derive(Clone, Copy, Finite)]
+enum TooSmall {
+  Low(u8),
+  High(u8),
+  #[finite(other)]
+  Unknown(u8),
+}
+
+#[derive(Clone, Copy, Finite)]
+#[finite(discriminant)]
+#[repr(i8)]
+enum Signed {
+  Negative = -1,
+  Positive = 1,
+}
+
+fn main() {}
@@ -0,0 +1,24 @@
+use finite_repr::Finite;
+
+#[derive(Clone, Copy, Finite)]
+#[finite(reserve = 4, table)]
+enum Version {
+  First,
+  Second,
+}
+
+#[derive(Clone, Copy, Finite)]
+#[finite(table)]
+struct Header {
+  version: Version,
+  flag: bool,
+}
+
+#[derive(Clone, Copy, Finite)]
+#[finite(table)]
+enum Packet {
+  Empty,
+  Versioned(Version),
+}
+
+fn main() {}
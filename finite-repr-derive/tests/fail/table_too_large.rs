@@ -0,0 +1,7 @@
+use finite_repr::Finite;
+
+#[derive(Clone, Copy, Finite)]
+#[finite(table)]
+struct TooLarge(u8, u8);
+
+fn main() {}
@@ -0,0 +1,21 @@
+use finite_repr::Finite;
+
+#[derive(Clone, Copy, Finite)]
+#[finite(tag = u8)]
+union Payload {
+  #[finite(tag = 0)]
+  flag: bool,
+  #[finite(tag = 1)]
+  byte: u8,
+}
+
+// Without `unsafe impl FiniteTagged for Message {}` safe code
+// could encode a `Message` whose tag names the wrong arm.
+#[derive(Clone, Copy, Finite)]
+struct Message {
+  kind: u8,
+  #[finite(tag = kind)]
+  payload: Payload,
+}
+
+fn main() {}
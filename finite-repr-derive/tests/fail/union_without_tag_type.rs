@@ -0,0 +1,9 @@
+use finite_repr::Finite;
+
+#[derive(Finite)]
+union Untagged {
+  a: u8,
+  b: bool,
+}
+
+fn main() {}
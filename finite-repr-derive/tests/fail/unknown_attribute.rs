@@ -0,0 +1,10 @@
+use finite_repr::Finite;
+
+#[derive(Finite)]
+#[finite(bitwise)]
+enum Unknown {
+  A,
+  B,
+}
+
+fn main() {}
@@ -0,0 +1,17 @@
+use finite_repr::Finite;
+
+#[derive(Clone, Copy, Finite)]
+#[finite(tag = u8)]
+union Payload {
+  #[finite(tag = 0)]
+  a: u8,
+}
+
+#[derive(Clone, Copy, Finite)]
+struct Message {
+  kind: u8,
+  #[finite(tag = knid)]
+  payload: Payload,
+}
+
+fn main() {}
@@ -0,0 +1,10 @@
+use finite_repr::Finite;
+
+#[derive(Finite)]
+enum OnVariant {
+  #[finite(tag = 0)]
+  A,
+  B,
+}
+
+fn main() {}
@@ -6,5 +6,23 @@ fn tests() {
   t.pass("tests/test_derive_repr.rs");
   t.pass("tests/test_derive_encoding.rs");
   t.pass("tests/test_derive_decoding.rs");
+  t.pass("tests/test_derive_finite.rs");
   t.pass("tests/test_isomorphism.rs");
+  t.pass("tests/test_hygiene.rs");
+  t.pass("tests/test_crate_path.rs");
+  t.pass("tests/test_tagged_union.rs");
+  t.pass("tests/test_discriminant.rs");
+  t.pass("tests/test_primitive_repr.rs");
+  t.pass("tests/test_table.rs");
+  t.pass("tests/test_bitpacked.rs");
+  t.pass("tests/test_lexicographic.rs");
+  t.pass("tests/test_finite_ord.rs");
+  t.pass("tests/test_signed_values.rs");
+  t.pass("tests/test_compact.rs");
+  t.pass("tests/test_fits.rs");
+  t.pass("tests/test_overflow.rs");
+  t.pass("tests/test_fallback.rs");
+  t.pass("tests/test_other.rs");
+  t.pass("tests/test_reserve.rs");
+  t.compile_fail("tests/fail/*.rs");
 }
@@ -0,0 +1,94 @@
+use finite_repr::{Finite, FiniteDecoding, FiniteEncoding, FiniteRepr};
+
+#[derive(Clone, Copy, PartialEq, Debug, Finite)]
+enum Colour {
+  Red,
+  Green,
+  Blue,
+}
+
+#[derive(Clone, Copy, PartialEq, Debug, Finite)]
+#[finite(bitpacked)]
+struct Pixel {
+  colour: Colour,
+  lit: bool,
+  level: u8,
+}
+
+#[derive(Clone, Copy, PartialEq, Debug, Finite)]
+#[finite(bitpacked)]
+struct Flags(bool, bool, bool);
+
+#[derive(Clone, Copy, PartialEq, Debug, Finite)]
+#[finite(bitpacked)]
+struct Palette(Colour, Colour, Colour);
+
+#[derive(Clone, Copy, PartialEq, Debug, Finite)]
+#[finite(bitpacked)]
+struct Generic<T>(Colour, T);
+
+#[derive(Clone, Copy, PartialEq, Debug, Finite)]
+#[finite(bitpacked)]
+struct Unit;
+
+fn main() {
+  // 2 bits for the colour, 1 for `lit` & 8 for the level.
+  assert_eq!(Pixel::REPRS, 1 << 11);
+  assert_eq!(Pixel::WASTED_BITS, 0);
+  // 6 bits, where 27 representations only need 5.
+  assert_eq!(Palette::REPRS, 64);
+  assert_eq!(Palette::WASTED_BITS, 1);
+  assert_eq!(Flags::REPRS, 8);
+  assert_eq!(Flags::WASTED_BITS, 0);
+  assert_eq!(Generic::<bool>::REPRS, 8);
+  assert_eq!(Unit::REPRS, 1);
+  assert_eq!(Unit::WASTED_BITS, 0);
+
+  let pixel = Pixel {
+    colour: Colour::Blue,
+    lit: true,
+    level: 0x81,
+  };
+  let number = (0x81 << 3) | (1 << 2) | 2u16;
+
+  assert_eq!(pixel.into_finite::<u16>(), Some(number));
+  assert_eq!(Pixel::from_finite(number), Some(pixel));
+
+  for number in 0..Pixel::REPRS as u16 {
+    match Pixel::from_finite(number) {
+      Some(pixel) => assert_eq!(pixel.into_finite::<u16>(), Some(number)),
+      // Only the fourth colour is an invalid bit pattern.
+      None => assert_eq!(number & 0b11, 0b11),
+    }
+  }
+
+  assert_eq!(Pixel::from_finite(Pixel::REPRS as u16), None);
+
+  // Only the number has to fit in the generic type, not the
+  // whole layout.
+  assert_eq!(pixel.into_finite::<u8>(), None);
+  let dim = Pixel {
+    colour: Colour::Green,
+    lit: true,
+    level: 0x1f,
+  };
+  assert_eq!(dim.into_finite::<u8>(), Some(0xfd));
+  assert_eq!(Pixel::from_finite(0xfdu8), Some(dim));
+
+  for (flags, number) in [
+    (Flags(false, false, false), 0),
+    (Flags(true, false, false), 1),
+    (Flags(false, true, false), 2),
+    (Flags(true, true, true), 7),
+  ] {
+    assert_eq!(flags.into_finite::<u8>(), Some(number));
+    assert_eq!(Flags::from_finite(number), Some(flags));
+  }
+
+  let generic = Generic(Colour::Green, true);
+  assert_eq!(generic.into_finite::<u8>(), Some((1 << 2) | 1));
+  assert_eq!(Generic::from_finite((1 << 2) | 1u8), Some(generic));
+
+  assert_eq!(Unit.into_finite::<u8>(), Some(0));
+  assert_eq!(Unit::from_finite(0u8), Some(Unit));
+}
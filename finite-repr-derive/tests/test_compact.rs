@@ -0,0 +1,44 @@
+use finite_repr::{Finite, FiniteCompact, FiniteRepr, Smallest};
+
+#[derive(Clone, Copy, PartialEq, Debug, Finite, FiniteCompact)]
+enum Suit {
+  Clubs,
+  Diamonds,
+  Hearts,
+  Spades,
+}
+
+#[derive(Clone, Copy, PartialEq, Debug, Finite, FiniteCompact)]
+struct Card {
+  suit: Suit,
+  rank: u8,
+}
+
+#[derive(Clone, Copy, PartialEq, Debug, Finite, FiniteCompact)]
+struct Hand(Card, Card, Option<Card>);
+
+#[derive(Clone, Copy, PartialEq, Debug, Finite, FiniteCompact)]
+struct Unit;
+
+fn main() {
+  assert_eq!(Suit::BITS_REQUIRED, 2);
+  assert_eq!(Card::BITS_REQUIRED, 10);
+  assert_eq!(Card::BYTES_REQUIRED, 2);
+  assert_eq!(Hand::BYTES_REQUIRED, 4);
+  assert_eq!(Unit::BYTES_REQUIRED, 0);
+
+  let _: Smallest<Suit> = 0u8;
+  let _: Smallest<Card> = 0u16;
+  let _: Smallest<Hand> = 0u32;
+  let _: Smallest<Unit> = 0u8;
+
+  let card = Card {
+    suit: Suit::Spades,
+    rank: 12,
+  };
+  let compact: u16 = card.into_compact().unwrap();
+  assert_eq!(Card::from_compact(compact), Some(card));
+
+  let hand = Hand(card, card, None);
+  assert_eq!(hand.into_compact().and_then(Hand::from_compact), Some(hand));
+}
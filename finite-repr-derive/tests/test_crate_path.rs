@@ -0,0 +1,48 @@
+/**
+ * Stands in for a crate re-exporting `finite_repr`, the
+ * derived code should only ever refer to it via the path
+ * given in `#[finite(crate = "...")]`.
+ */
+mod facade {
+  pub use finite_repr as reexported;
+}
+
+use facade::reexported::{FiniteDecoding, FiniteEncoding, FiniteRepr};
+
+fn main() {
+  #[derive(Clone, Copy, PartialEq, Debug, FiniteRepr, FiniteDecoding, FiniteEncoding)]
+  #[finite(crate = "crate::facade::reexported")]
+  enum ViaFacade {
+    A(bool),
+    B,
+  }
+
+  assert_eq!(ViaFacade::REPRS, 3);
+
+  for value in [ViaFacade::A(false), ViaFacade::A(true), ViaFacade::B] {
+    assert_eq!(
+      Some(value),
+      value
+        .into_finite::<u16>()
+        .and_then(FiniteDecoding::from_finite)
+    );
+  }
+
+  #[derive(Clone, Copy, PartialEq, Debug, FiniteRepr, FiniteDecoding, FiniteEncoding)]
+  #[finite(crate = "facade::reexported")]
+  struct ViaRelativePath(ViaFacade, bool);
+
+  assert_eq!(ViaRelativePath::REPRS, 6);
+
+  for value in [
+    ViaRelativePath(ViaFacade::A(true), false),
+    ViaRelativePath(ViaFacade::B, true),
+  ] {
+    assert_eq!(
+      Some(value),
+      value
+        .into_finite::<u16>()
+        .and_then(FiniteDecoding::from_finite)
+    );
+  }
+}
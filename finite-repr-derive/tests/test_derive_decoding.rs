@@ -1,4 +1,4 @@
-use finite_repr::{FiniteDecoding, FiniteRepr};
+use finite_repr::{FiniteDecoding, FiniteEncoding, FiniteRepr};
 
 fn main() {
   #[derive(Clone, Copy, PartialEq, Debug, FiniteRepr, FiniteDecoding)]
@@ -91,6 +91,49 @@ fn main() {
   for (d, value) in pairs(vec![(VoidStruct, 0)]) {
     assert_eq!(Some(d), FiniteDecoding::from_finite(value));
   }
+
+  #[derive(Clone, Copy, PartialEq, Debug, FiniteRepr, FiniteDecoding)]
+  enum Never {}
+
+  // Variants without any representations are skipped over
+  // when searching for the variant of a number.
+  #[derive(Clone, Copy, PartialEq, Debug, FiniteRepr, FiniteDecoding)]
+  enum HasEmptyVariants {
+    A(Never),
+    B(bool),
+    C(Never),
+    D,
+    E(Never),
+  }
+
+  for (d, value) in pairs(vec![
+    (Some(HasEmptyVariants::B(false)), 0),
+    (Some(HasEmptyVariants::B(true)), 1),
+    (Some(HasEmptyVariants::D), 2),
+    (None, 3),
+  ]) {
+    assert_eq!(d, FiniteDecoding::from_finite(value));
+  }
+
+  assert_eq!(None, Never::from_finite(0u16));
+
+  // Every value of the number is used, so the radix of the
+  // type doesn't fit in it but decoding still succeeds.
+  #[derive(Clone, Copy, PartialEq, Debug, FiniteRepr, FiniteDecoding, FiniteEncoding)]
+  struct Wrapper(u8);
+
+  assert_eq!(Some(Wrapper(255)), Wrapper::from_finite(255u8));
+  assert_eq!(Some(255u8), Wrapper(255).into_finite());
+
+  #[derive(Clone, Copy, PartialEq, Debug, FiniteRepr, FiniteDecoding, FiniteEncoding)]
+  enum Wide {
+    A(u8),
+    B,
+  }
+
+  assert_eq!(Some(Wide::A(255)), Wide::from_finite(255u8));
+  assert_eq!(None, Wide::B.into_finite::<u8>());
+  assert_eq!(Some(256u16), Wide::B.into_finite());
 }
 
 fn pairs<T>(values: Vec<(T, u16)>) -> <Vec<(T, u16)> as IntoIterator>::IntoIter {
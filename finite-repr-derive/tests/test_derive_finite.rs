@@ -0,0 +1,57 @@
+use finite_repr::{Finite, FiniteDecoding, FiniteEncoding, FiniteRepr};
+
+fn main() {
+  #[derive(Clone, Copy, PartialEq, Debug, Finite)]
+  enum HasRecord {
+    A { a: bool, b: bool },
+    B(bool),
+    C,
+  }
+
+  assert_eq!(HasRecord::REPRS, 7);
+
+  for (value, number) in pairs(vec![
+    (HasRecord::A { a: false, b: false }, 0),
+    (HasRecord::A { a: true, b: false }, 1),
+    (HasRecord::A { a: false, b: true }, 2),
+    (HasRecord::A { a: true, b: true }, 3),
+    (HasRecord::B(false), 4),
+    (HasRecord::B(true), 5),
+    (HasRecord::C, 6),
+  ]) {
+    assert_eq!(value.into_finite::<u16>(), Some(number));
+    assert_eq!(Some(value), FiniteDecoding::from_finite(number));
+  }
+
+  #[derive(Clone, Copy, PartialEq, Debug, Finite)]
+  struct GenericRecord<A> {
+    pub a: A,
+    pub b: bool,
+  }
+
+  assert_eq!(GenericRecord::<HasRecord>::REPRS, 14);
+
+  for (value, number) in pairs(vec![
+    (
+      GenericRecord {
+        a: HasRecord::C,
+        b: false,
+      },
+      6,
+    ),
+    (
+      GenericRecord {
+        a: HasRecord::B(false),
+        b: true,
+      },
+      11,
+    ),
+  ]) {
+    assert_eq!(value.into_finite::<u16>(), Some(number));
+    assert_eq!(Some(value), FiniteDecoding::from_finite(number));
+  }
+}
+
+fn pairs<T>(values: Vec<(T, u16)>) -> <Vec<(T, u16)> as IntoIterator>::IntoIter {
+  values.into_iter()
+}
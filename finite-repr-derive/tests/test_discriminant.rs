@@ -0,0 +1,50 @@
+use finite_repr::{Finite, FiniteDecoding, FiniteEncoding, FiniteRepr};
+
+fn main() {
+  #[derive(Clone, Copy, PartialEq, Debug, Finite)]
+  #[finite(discriminant)]
+  #[repr(u8)]
+  enum Opcode {
+    Nop = 0x00,
+    Jmp = 0x10,
+    Ret = 0xC3,
+  }
+
+  assert_eq!(Opcode::REPRS, 0xC4);
+
+  for (value, number) in [
+    (Opcode::Nop, 0x00),
+    (Opcode::Jmp, 0x10),
+    (Opcode::Ret, 0xC3),
+  ] {
+    assert_eq!(value.into_finite::<u8>(), Some(number));
+    assert_eq!(Some(value), Opcode::from_finite(number));
+  }
+
+  for hole in [0x01u8, 0x0F, 0x11, 0xC2, 0xC4, 0xFF] {
+    assert_eq!(None, Opcode::from_finite(hole));
+  }
+
+  // Implicit discriminants follow the previous one.
+  #[derive(Clone, Copy, PartialEq, Debug, Finite)]
+  #[finite(discriminant)]
+  enum Implicit {
+    A = 2,
+    B,
+    C = 7,
+  }
+
+  assert_eq!(Implicit::REPRS, 8);
+  assert_eq!(Implicit::B.into_finite::<u16>(), Some(3));
+  assert_eq!(Implicit::from_finite(7u16), Some(Implicit::C));
+  assert_eq!(Implicit::from_finite(0u16), None);
+
+  // Nested in another type the whole span is reserved.
+  #[derive(Clone, Copy, PartialEq, Debug, Finite)]
+  struct Instruction(Opcode, bool);
+
+  assert_eq!(Instruction::REPRS, 0xC4 * 2);
+  let instruction = Instruction(Opcode::Ret, true);
+  assert_eq!(instruction.into_finite::<u16>(), Some(0xC3 + 0xC4));
+  assert_eq!(Instruction::from_finite(0xC3u16 + 0xC4), Some(instruction));
+}
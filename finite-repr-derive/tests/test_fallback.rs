@@ -0,0 +1,41 @@
+use finite_repr::{Finite, FiniteDecoding, FiniteFallback, FiniteRepr, Overflow};
+
+#[derive(Clone, Copy, PartialEq, Debug, Finite)]
+enum Weather {
+  Sunny,
+  Rainy(bool),
+  #[finite(fallback)]
+  Unknown,
+}
+
+#[derive(Clone, Copy, PartialEq, Debug, Finite)]
+#[finite(discriminant)]
+#[repr(u8)]
+enum Status {
+  Ok = 1,
+  Busy = 3,
+  #[finite(fallback)]
+  Other = 7,
+}
+
+fn main() {
+  assert_eq!(Weather::REPRS, 4);
+  assert_eq!(Weather::fallback(), Weather::Unknown);
+  assert_eq!(Weather::from_finite_or_fallback(2u8), Weather::Rainy(true));
+  assert_eq!(Weather::from_finite_or_fallback(9u8), Weather::Unknown);
+
+  assert_eq!(Weather::from_finite_with(9u8, Overflow::Checked), None);
+  assert_eq!(
+    Weather::from_finite_with(9u8, Overflow::Saturating),
+    Some(Weather::Unknown)
+  );
+  assert_eq!(
+    Weather::from_finite_with(9u8, Overflow::Wrapping),
+    Some(Weather::Rainy(false))
+  );
+
+  // Gaps between discriminants don't decode either.
+  assert_eq!(Status::from_finite_or_fallback(3u8), Status::Busy);
+  assert_eq!(Status::from_finite_or_fallback(2u8), Status::Other);
+  assert_eq!(Status::from_finite_or_fallback(200u8), Status::Other);
+}
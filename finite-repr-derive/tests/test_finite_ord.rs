@@ -0,0 +1,114 @@
+use finite_repr::{Finite, FiniteDecoding, FiniteEncoding, FiniteOrd};
+use std::cmp::Ordering;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Finite, FiniteOrd)]
+enum Level {
+  Low,
+  Mid,
+  High,
+}
+
+// The first field is the least significant, so it's
+// compared last.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Finite, FiniteOrd)]
+struct Dense {
+  level: Level,
+  flag: bool,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Finite, FiniteOrd)]
+#[finite(order = "lexicographic")]
+struct Sorted(Level, bool);
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Finite, FiniteOrd)]
+enum Shape {
+  Empty,
+  Line(Level, bool),
+  Point { level: Level },
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Finite, FiniteOrd)]
+#[finite(discriminant)]
+enum Opcode {
+  Ret = 3,
+  Nop = 0,
+  Jmp = 1,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Finite, FiniteOrd)]
+#[finite(bitpacked)]
+struct Packed(Level, Level);
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Finite, FiniteOrd)]
+struct Generic<T>(T, Level);
+
+// `Ord` orders `None` first & tuples by their first element,
+// which isn't how either of them encode.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Finite, FiniteOrd)]
+struct WithOpt {
+  a: Option<bool>,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Finite, FiniteOrd)]
+struct WithTuple(bool, (bool, bool));
+
+// Derived field types compare field by field rather than
+// encoding, which should still agree with their numbers.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Finite, FiniteOrd)]
+struct Nested(Dense, Option<Shape>, (Level, bool));
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Finite, FiniteOrd)]
+struct Unit;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Finite, FiniteOrd)]
+enum Single {
+  Only(bool),
+}
+
+fn main() {
+  assert_consistent::<Level>();
+  assert_consistent::<Dense>();
+  assert_consistent::<Sorted>();
+  assert_consistent::<Shape>();
+  assert_consistent::<Opcode>();
+  assert_consistent::<Packed>();
+  assert_consistent::<Generic<bool>>();
+  assert_consistent::<WithOpt>();
+  assert_consistent::<WithTuple>();
+  assert_consistent::<Generic<Option<bool>>>();
+  assert_consistent::<Nested>();
+  assert_consistent::<Unit>();
+  assert_consistent::<Single>();
+
+  let low = Dense {
+    level: Level::High,
+    flag: false,
+  };
+  let high = Dense {
+    level: Level::Low,
+    flag: true,
+  };
+  assert!(low < high);
+  assert!(Sorted(Level::Low, true) < Sorted(Level::High, false));
+  assert!(Opcode::Nop < Opcode::Ret);
+  assert!(WithOpt { a: Some(true) } < WithOpt { a: None });
+  assert!(WithTuple(false, (false, true)) > WithTuple(false, (true, false)));
+}
+
+/**
+ * Every pair of values compares the same as their encodings.
+ */
+fn assert_consistent<T: FiniteDecoding + FiniteEncoding + Ord + std::fmt::Debug>() {
+  let values: Vec<T> = (0..T::REPRS as u16).filter_map(T::from_finite).collect();
+
+  for a in values.iter() {
+    for b in values.iter() {
+      let a_number = a.into_finite::<u16>().unwrap();
+      let b_number = b.into_finite::<u16>().unwrap();
+      assert_eq!(a.cmp(b), a_number.cmp(&b_number), "{:?} & {:?}", a, b);
+      assert_eq!(a.partial_cmp(b), Some(a.cmp(b)));
+    }
+  }
+
+  assert!(values.iter().all(|v| v.cmp(v) == Ordering::Equal));
+}
@@ -0,0 +1,31 @@
+use finite_repr::{BigEndian, Finite, FiniteRepr, FiniteTarget, JsSafeInt};
+use std::num::NonZeroU8;
+
+#[derive(Clone, Copy, Finite)]
+#[finite(fits = "u8")]
+struct Flags(bool, bool, bool, bool, bool, bool, bool, bool);
+
+#[derive(Clone, Copy, Finite)]
+#[finite(fits = "NonZeroU8")]
+enum Level {
+  Low,
+  Middle,
+  High,
+}
+
+#[derive(Clone, Copy, Finite)]
+#[finite(fits = "BigEndian<2>")]
+struct Pair(u8, u8);
+
+#[derive(Clone, Copy, Finite)]
+#[finite(fits = "JsSafeInt")]
+struct Id(u8, u8, u8, u8);
+
+fn main() {
+  assert_eq!(Flags::REPRS, <u8 as FiniteTarget>::CAPACITY);
+  assert_eq!(<NonZeroU8 as FiniteTarget>::CAPACITY, 255);
+  assert_eq!(Pair::REPRS, <BigEndian<2> as FiniteTarget>::CAPACITY);
+  assert_eq!(<JsSafeInt as FiniteTarget>::CAPACITY, 1 << 53);
+  assert_eq!(Level::REPRS, 3);
+  assert_eq!(Id::REPRS, 1 << 32);
+}
@@ -0,0 +1,107 @@
+use finite_repr::{FiniteDecoding, FiniteEncoding};
+
+/**
+ * Everything in this module shadows a name the generated code
+ * could plausibly use, if the derives aren't hygienic this module
+ * will fail to compile.
+ */
+#[allow(dead_code, non_upper_case_globals, non_camel_case_types)]
+mod shadowed {
+  use finite_repr::{FiniteDecoding, FiniteEncoding, FiniteRepr};
+
+  pub struct Option;
+  pub struct Some;
+  pub struct None;
+  pub struct T;
+  pub struct __FiniteValueT;
+
+  pub const value: u8 = 0;
+  pub const base: u8 = 0;
+  pub const number: u8 = 0;
+  pub const field_0: u8 = 0;
+  pub const size_1: u8 = 0;
+
+  pub trait FiniteValue {}
+
+  #[derive(Clone, Copy, PartialEq, Debug, FiniteRepr, FiniteDecoding, FiniteEncoding)]
+  pub struct FieldNamedValue {
+    pub value: bool,
+    pub base: bool,
+    pub number: bool,
+  }
+
+  #[derive(Clone, Copy, PartialEq, Debug, FiniteRepr, FiniteDecoding, FiniteEncoding)]
+  pub struct GenericNamedT<T> {
+    pub value: T,
+    pub size_1: bool,
+  }
+
+  #[derive(Clone, Copy, PartialEq, Debug, FiniteRepr, FiniteDecoding, FiniteEncoding)]
+  pub enum EnumNamedFields<T> {
+    Value { value: T, field_0: bool },
+    Base(bool),
+    None,
+  }
+}
+
+use shadowed::{EnumNamedFields, FieldNamedValue, GenericNamedT};
+
+fn main() {
+  for value in [
+    FieldNamedValue {
+      value: false,
+      base: true,
+      number: false,
+    },
+    FieldNamedValue {
+      value: true,
+      base: false,
+      number: true,
+    },
+  ] {
+    assert_eq!(
+      Some(value),
+      value
+        .into_finite::<u16>()
+        .and_then(FiniteDecoding::from_finite)
+    );
+  }
+
+  for value in [
+    GenericNamedT {
+      value: false,
+      size_1: true,
+    },
+    GenericNamedT {
+      value: true,
+      size_1: false,
+    },
+  ] {
+    assert_eq!(
+      Some(value),
+      value
+        .into_finite::<u16>()
+        .and_then(FiniteDecoding::from_finite)
+    );
+  }
+
+  for value in [
+    EnumNamedFields::Value {
+      value: true,
+      field_0: false,
+    },
+    EnumNamedFields::Value {
+      value: false,
+      field_0: true,
+    },
+    EnumNamedFields::Base(true),
+    EnumNamedFields::None,
+  ] {
+    assert_eq!(
+      Some(value),
+      value
+        .into_finite::<u16>()
+        .and_then(FiniteDecoding::from_finite)
+    );
+  }
+}
@@ -114,6 +114,25 @@ fn main() {
     );
   }
 
+  #[derive(Clone, Copy, PartialEq, Debug, FiniteRepr, FiniteDecoding, FiniteEncoding)]
+  struct MixedRadix(bool, HasRecord, Has2Reprs);
+
+  for value in pairs(vec![
+    MixedRadix(false, HasRecord::A { a: false, b: false }, Has2Reprs::P1),
+    MixedRadix(true, HasRecord::A { a: true, b: false }, Has2Reprs::P1),
+    MixedRadix(false, HasRecord::B(true), Has2Reprs::P1),
+    MixedRadix(true, HasRecord::A { a: false, b: true }, Has2Reprs::P2),
+    MixedRadix(false, HasRecord::B(false), Has2Reprs::P2),
+    MixedRadix(true, HasRecord::B(true), Has2Reprs::P2),
+  ]) {
+    assert_eq!(
+      Some(value),
+      value
+        .into_finite::<u16>()
+        .and_then(FiniteDecoding::from_finite)
+    );
+  }
+
   #[derive(Clone, Copy, PartialEq, Debug, FiniteRepr, FiniteDecoding, FiniteEncoding)]
   struct VoidStruct;
 
@@ -0,0 +1,74 @@
+use finite_repr::{Finite, FiniteDecoding, FiniteEncoding, FiniteRepr, Lexicographic};
+
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug, Finite)]
+#[finite(table)]
+enum Level {
+  Low,
+  High,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug, Finite)]
+#[finite(order = "lexicographic")]
+struct Key {
+  level: Level,
+  id: u8,
+  active: bool,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug, Finite)]
+#[finite(order = "lexicographic")]
+enum Event {
+  Start,
+  Move(bool, Level),
+  Stop { code: u8, fatal: bool },
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug, Finite)]
+#[finite(order = "lexicographic", table)]
+struct Cell(Level, bool, Level);
+
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug, Finite)]
+#[finite(order = "lexicographic", bitpacked)]
+struct Packed(Level, u8);
+
+fn main() {
+  assert_eq!(Key::REPRS, 2 * 256 * 2);
+  assert_eq!(
+    Key {
+      level: Level::High,
+      id: 3,
+      active: true
+    }
+    .into_finite::<u16>(),
+    Some(512 + 3 * 2 + 1)
+  );
+
+  assert_sorted::<Key>();
+  assert_sorted::<Event>();
+  assert_sorted::<Cell>();
+  assert_sorted::<Packed>();
+  assert_sorted::<Lexicographic<(Option<bool>, Level)>>();
+  assert_sorted::<Lexicographic<(bool, Option<Level>, bool)>>();
+
+  // `None` is encoded last, so it also compares last.
+  assert!(Lexicographic((None, Level::Low)) > Lexicographic((Some(true), Level::High)));
+
+  assert_eq!(Cell::ALL[1], Cell(Level::Low, false, Level::High));
+  assert_eq!(Cell(Level::High, false, Level::Low).index(), 4);
+  assert_eq!(Packed(Level::High, 1).into_finite::<u16>(), Some(256 + 1));
+}
+
+/**
+ * Decoding every number in turn should produce values in
+ * the order of their derived `Ord`.
+ */
+fn assert_sorted<T: FiniteDecoding + FiniteEncoding + Ord + std::fmt::Debug>() {
+  let values: Vec<T> = (0..T::REPRS as u16).filter_map(T::from_finite).collect();
+
+  assert!(values.windows(2).all(|pair| pair[0] < pair[1]));
+
+  for value in values.iter() {
+    let number = value.into_finite::<u16>().unwrap();
+    assert_eq!(T::from_finite(number).as_ref(), Some(value));
+  }
+}
@@ -0,0 +1,56 @@
+use finite_repr::{Finite, FiniteDecoding, FiniteEncoding, FiniteOrd, FiniteRepr};
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Finite, FiniteOrd)]
+enum Command {
+  Stop,
+  Move(bool),
+  #[finite(other)]
+  Unknown(u16),
+}
+
+#[derive(Clone, Copy, PartialEq, Debug, Finite)]
+enum Newer {
+  Stop,
+  Move(bool),
+  Turn(u8),
+  Wait,
+}
+
+#[derive(Clone, Copy, PartialEq, Debug, Finite)]
+struct Packet {
+  id: u8,
+  command: Command,
+}
+
+fn main() {
+  // The catch-all spans every number its field can hold.
+  assert_eq!(Command::REPRS, 1 << 16);
+  assert_eq!(Command::Move(true).into_finite::<u16>(), Some(2));
+  assert_eq!(Command::from_finite(2u16), Some(Command::Move(true)));
+
+  // Numbers from newer variants decode as unknown & encode unchanged.
+  for newer in [Newer::Turn(0), Newer::Turn(u8::MAX), Newer::Wait].iter() {
+    let number = newer.into_finite::<u32>().unwrap();
+    let command = Command::from_finite(number).unwrap();
+    assert_eq!(command, Command::Unknown(number as u16));
+    assert_eq!(command.into_finite::<u32>(), Some(number));
+    assert_eq!(Newer::from_finite(number), Some(*newer));
+  }
+
+  // Unknown numbers that belong to a known variant don't encode.
+  assert_eq!(Command::Unknown(1).into_finite::<u16>(), None);
+  assert_eq!(Command::Unknown(3).into_finite::<u8>(), Some(3));
+  assert_eq!(Command::Unknown(300).into_finite::<u8>(), None);
+  assert_eq!(Command::from_finite(1u32 << 16), None);
+
+  let packet = Packet {
+    id: 7,
+    command: Command::Unknown(1000),
+  };
+  let number = packet.into_finite::<u32>().unwrap();
+  assert_eq!(Packet::from_finite(number), Some(packet));
+
+  assert!(Command::Stop < Command::Move(false));
+  assert!(Command::Move(true) < Command::Unknown(3));
+  assert!(Command::Unknown(3) < Command::Unknown(4));
+}
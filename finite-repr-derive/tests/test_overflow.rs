@@ -0,0 +1,81 @@
+use finite_repr::{Finite, FiniteDecoding, FiniteEncoding, FiniteRepr, FiniteTagged, Overflow};
+
+#[derive(Clone, Copy, PartialEq, Debug, Finite)]
+struct Pair(u8, bool);
+
+#[derive(Clone, Copy, PartialEq, Debug, Finite)]
+struct Triple(u8, u8, bool);
+
+#[derive(Clone, Copy, PartialEq, Debug, Finite)]
+enum Event {
+  Idle,
+  Key(u8),
+  Click(Pair),
+}
+
+#[derive(Clone, Copy, Finite)]
+#[finite(tag = u8)]
+union Payload {
+  #[finite(tag = 0)]
+  flag: bool,
+  #[finite(tag = 1)]
+  byte: u8,
+}
+
+#[derive(Clone, Copy, Finite)]
+struct Message {
+  kind: u8,
+  #[finite(tag = kind)]
+  payload: Payload,
+}
+
+// SAFETY: every `Message` is built with `kind` matching the
+// initialised arm of `payload`.
+unsafe impl FiniteTagged for Message {}
+
+fn main() {
+  // Numbers too large for the target fail to encode rather
+  // than panicking or wrapping.
+  assert_eq!(Pair(u8::MAX, true).into_finite::<u8>(), None);
+  assert_eq!(Pair(u8::MAX, true).into_finite::<u16>(), Some(511));
+
+  // A place value too large for the target only fails when
+  // something is placed there, the same as decoding.
+  assert_eq!(Pair(5, false).into_finite::<u8>(), Some(5));
+  assert_eq!(Pair::from_finite(5u8), Some(Pair(5, false)));
+  let triple = Triple(u8::MAX, u8::MAX, false);
+  assert_eq!(triple.into_finite::<u16>(), Some(u16::MAX));
+  assert_eq!(Triple::from_finite(u16::MAX), Some(triple));
+
+  assert_eq!(Event::REPRS, 1 + 256 + 512);
+  assert_eq!(Event::Key(254).into_finite::<u8>(), Some(u8::MAX));
+  assert_eq!(Event::Key(u8::MAX).into_finite::<u8>(), None);
+  assert_eq!(Event::Click(Pair(0, false)).into_finite::<u8>(), None);
+  assert_eq!(Event::from_finite(u8::MAX), Some(Event::Key(254)));
+
+  let message = Message {
+    kind: 1,
+    payload: Payload { byte: u8::MAX },
+  };
+  assert_eq!(message.into_finite::<u8>(), None);
+  assert_eq!(message.into_finite::<u16>(), Some(2 + 255));
+
+  // Derived encodings overflow as a whole number.
+  let click = Event::Click(Pair(3, true));
+  let exact = click.into_finite::<u16>().unwrap();
+  assert_eq!(click.into_finite_with::<u8>(Overflow::Checked), None);
+  assert_eq!(
+    click.into_finite_with::<u8>(Overflow::Saturating),
+    Some(u8::MAX)
+  );
+  assert_eq!(
+    click.into_finite_with::<u8>(Overflow::Wrapping),
+    Some(exact as u8)
+  );
+
+  // The policies only differ for numbers that don't fit.
+  for overflow in [Overflow::Checked, Overflow::Saturating, Overflow::Wrapping] {
+    assert_eq!(Pair(5, false).into_finite_with::<u8>(overflow), Some(5));
+    assert_eq!(triple.into_finite_with::<u16>(overflow), Some(u16::MAX));
+  }
+}
@@ -0,0 +1,78 @@
+use finite_repr::{Finite, FiniteDecoding, FiniteEncoding, FiniteRepr};
+
+fn main() {
+  #[derive(PartialEq, Debug, Finite)]
+  #[repr(u8)]
+  enum Direction {
+    North,
+    East,
+    South,
+    West,
+  }
+
+  assert_eq!(Direction::REPRS, 4);
+
+  for (value, number) in [
+    (Direction::North, 0),
+    (Direction::East, 1),
+    (Direction::South, 2),
+    (Direction::West, 3),
+  ] {
+    assert_eq!(value.into_finite::<u8>(), Some(number));
+    assert_eq!(Some(value), Direction::from_finite(number));
+  }
+
+  assert_eq!(None, Direction::from_finite(4u8));
+
+  // Still encoded by position, the first discriminant is
+  // subtracted from the cast.
+  #[derive(Clone, Copy, PartialEq, Debug, Finite)]
+  #[repr(u16)]
+  enum Offset {
+    A = 500,
+    B,
+    C = 502,
+  }
+
+  assert_eq!(Offset::REPRS, 3);
+
+  for (value, number) in [(Offset::A, 0), (Offset::B, 1), (Offset::C, 2)] {
+    assert_eq!(value.into_finite::<u16>(), Some(number));
+    assert_eq!(Some(value), Offset::from_finite(number));
+  }
+
+  assert_eq!(None, Offset::from_finite(3u16));
+  assert_eq!(None, Offset::from_finite(500u16));
+
+  // Gaps between discriminants fall back to the general
+  // encoding, which is the same.
+  #[derive(Clone, Copy, PartialEq, Debug, Finite)]
+  #[repr(u8)]
+  enum Gap {
+    A = 1,
+    B = 3,
+  }
+
+  assert_eq!(Gap::B.into_finite::<u8>(), Some(1));
+  assert_eq!(Gap::from_finite(1u8), Some(Gap::B));
+
+  use shadowed::Shadowed;
+
+  assert_eq!(Shadowed::B.into_finite::<u16>(), Some(1));
+  assert_eq!(Shadowed::from_finite(1u16), Some(Shadowed::B));
+}
+
+// A type named like the repr doesn't confuse the cast.
+mod shadowed {
+  use finite_repr::Finite;
+
+  #[allow(non_camel_case_types, dead_code)]
+  struct u8;
+
+  #[derive(Clone, Copy, PartialEq, Debug, Finite)]
+  #[repr(u8)]
+  pub enum Shadowed {
+    A,
+    B,
+  }
+}
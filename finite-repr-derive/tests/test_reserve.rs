@@ -0,0 +1,119 @@
+use finite_repr::{DecodeError, Finite, FiniteDecoding, FiniteEncoding, FiniteRepr, Overflow};
+
+#[derive(Clone, Copy, PartialEq, Debug, Finite)]
+#[finite(reserve = 8)]
+enum Colour {
+  Red,
+  Green,
+  Blue(bool),
+}
+
+#[derive(Clone, Copy, PartialEq, Debug, Finite)]
+#[finite(reserve = 8)]
+enum NewerColour {
+  Red,
+  Green,
+  Blue(bool),
+  Purple,
+}
+
+#[derive(Clone, Copy, PartialEq, Debug, Finite)]
+struct Pixel {
+  colour: Colour,
+  lit: bool,
+}
+
+#[derive(Clone, Copy, PartialEq, Debug, Finite)]
+enum Event {
+  Idle,
+  Paint(Pixel),
+}
+
+#[derive(Clone, Copy, PartialEq, Debug, Finite)]
+#[finite(discriminant)]
+enum Gap {
+  Low = 0,
+  High = 2,
+}
+
+#[derive(Clone, Copy, PartialEq, Debug, Finite)]
+#[finite(bitpacked)]
+struct Packed(Colour, Gap);
+
+#[derive(Clone, Copy, PartialEq, Debug, Finite)]
+#[finite(reserve = 1024)]
+struct Header {
+  version: u8,
+  flag: bool,
+}
+
+#[derive(Clone, Copy, PartialEq, Debug, Finite)]
+#[finite(table, reserve = 16)]
+enum Small {
+  A,
+  B,
+}
+
+fn main() {
+  assert_eq!(Colour::REPRS, 8);
+  assert_eq!(Colour::USED_REPRS, 4);
+  assert_eq!(NewerColour::USED_REPRS, 5);
+  assert_eq!(Header::REPRS, 1024);
+  assert_eq!(Header::USED_REPRS, 512);
+  assert_eq!(Small::ALL.len(), 2);
+
+  // Adding a variant doesn't change the radix of a field.
+  assert_eq!(Pixel::REPRS, 16);
+  let pixel = Pixel {
+    colour: Colour::Blue(true),
+    lit: true,
+  };
+  let number = pixel.into_finite::<u8>().unwrap();
+  assert_eq!(number, 3 + 8);
+  assert_eq!(Pixel::from_finite(number), Some(pixel));
+
+  let purple = NewerColour::Purple.into_finite::<u8>().unwrap();
+  assert_eq!(Colour::from_finite(purple), None);
+  assert_eq!(Colour::try_from_finite(purple), Err(DecodeError::Reserved));
+  assert_eq!(Colour::try_from_finite(8u8), Err(DecodeError::OutOfRange));
+  assert_eq!(Colour::try_from_finite(-1i8), Err(DecodeError::OutOfRange));
+  assert_eq!(Colour::try_from_finite(3u8), Ok(Colour::Blue(true)));
+  assert_eq!(Small::try_from_finite(2u8), Err(DecodeError::Reserved));
+  assert_eq!(Header::try_from_finite(600u16), Err(DecodeError::Reserved));
+
+  // A reserved slot of a nested type is still reserved.
+  let reserved = purple + 8;
+  assert_eq!(Pixel::from_finite(reserved), None);
+  assert_eq!(Pixel::try_from_finite(reserved), Err(DecodeError::Reserved));
+  assert_eq!(Pixel::try_from_finite(16u8), Err(DecodeError::OutOfRange));
+  assert_eq!(
+    Event::try_from_finite(1 + reserved),
+    Err(DecodeError::Reserved)
+  );
+  assert_eq!(
+    Option::<Colour>::try_from_finite(purple),
+    Err(DecodeError::Reserved)
+  );
+  assert_eq!(
+    <(bool, Colour)>::try_from_finite(purple * 2),
+    Err(DecodeError::Reserved)
+  );
+
+  // Any other field rejecting the number makes it invalid.
+  assert_eq!(Packed::try_from_finite(1u8 << 3), Err(DecodeError::Invalid));
+  assert_eq!(
+    Packed::try_from_finite(purple | (1 << 3)),
+    Err(DecodeError::Invalid)
+  );
+  assert_eq!(Packed::try_from_finite(purple), Err(DecodeError::Reserved));
+
+  // Reserved numbers clamp to the last value in use.
+  assert_eq!(
+    Colour::from_finite_with(purple, Overflow::Saturating),
+    Some(Colour::Blue(true))
+  );
+  assert_eq!(
+    Colour::from_finite_with(9u8, Overflow::Wrapping),
+    Some(Colour::Green)
+  );
+}
@@ -0,0 +1,40 @@
+use finite_repr::{Finite, FiniteDecoding, FiniteEncoding, FiniteRepr};
+
+#[derive(Clone, Copy, PartialEq, Debug, Finite)]
+enum Shape {
+  Empty,
+  Line(bool, Option<bool>),
+  Braced {},
+  Tuple(),
+}
+
+#[derive(Clone, Copy, PartialEq, Debug, Finite)]
+struct Braced {}
+
+#[derive(Clone, Copy, PartialEq, Debug, Finite)]
+struct Tuple();
+
+fn main() {
+  // Fields that are empty have a single value, like a unit.
+  assert_eq!(Braced::REPRS, 1);
+  assert_eq!(Tuple::REPRS, 1);
+  assert_eq!(Shape::REPRS, 1 + 2 * 3 + 1 + 1);
+
+  assert_eq!(Braced {}.into_finite::<u8>(), Some(0));
+  assert_eq!(Braced::from_finite(0u8), Some(Braced {}));
+  assert_eq!(Braced::from_finite(1u8), None);
+  assert_eq!(Tuple::from_finite(0u8), Some(Tuple()));
+
+  // Signed numbers encode the same as unsigned ones, using
+  // only their non-negative values.
+  for number in 0..Shape::REPRS as u8 {
+    let shape = Shape::from_finite(number).unwrap();
+    assert_eq!(shape.into_finite::<i8>(), Some(number as i8));
+    assert_eq!(shape.into_finite::<isize>(), Some(number as isize));
+    assert_eq!(Shape::from_finite(number as i64), Some(shape));
+  }
+
+  assert_eq!(Shape::from_finite(-1i8), None);
+  assert_eq!(Shape::from_finite(i8::MIN), None);
+  assert_eq!(Shape::from_finite(Shape::REPRS as i8), None);
+}
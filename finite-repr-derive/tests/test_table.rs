@@ -0,0 +1,91 @@
+use finite_repr::{Finite, FiniteDecoding, FiniteEncoding, FiniteRepr};
+
+#[derive(Clone, Copy, PartialEq, Debug, Finite)]
+#[finite(table)]
+enum Suit {
+  Clubs,
+  Diamonds,
+  Hearts,
+  Spades,
+}
+
+#[derive(Clone, Copy, PartialEq, Debug, Finite)]
+#[finite(table)]
+struct Card {
+  suit: Suit,
+  rank: u8,
+  face_up: bool,
+}
+
+#[derive(Clone, Copy, PartialEq, Debug, Finite)]
+#[finite(table)]
+enum Slot {
+  Empty,
+  Card(Suit, bool),
+  Joker { red: bool },
+}
+
+#[derive(Clone, Copy, PartialEq, Debug, Finite)]
+#[finite(table)]
+struct Unit;
+
+// The table & index can be used in const contexts.
+const LAST_SUIT: Suit = Suit::ALL[3];
+const HEARTS: usize = Suit::Hearts.index();
+
+fn main() {
+  assert_eq!(LAST_SUIT, Suit::Spades);
+  assert_eq!(HEARTS, 2);
+  assert_eq!(Suit::ALL.len(), Suit::REPRS);
+
+  assert_eq!(Card::REPRS, 4 * 256 * 2);
+  assert_eq!(Slot::REPRS, 1 + 8 + 2);
+  assert_eq!(Unit::ALL, [Unit]);
+
+  assert_table_is_consistent(&Suit::ALL);
+  assert_table_is_consistent(&Card::ALL);
+  assert_table_is_consistent(&Slot::ALL);
+  assert_table_is_consistent(&Unit::ALL);
+
+  assert_eq!(
+    Slot::ALL,
+    [
+      Slot::Empty,
+      Slot::Card(Suit::Clubs, false),
+      Slot::Card(Suit::Diamonds, false),
+      Slot::Card(Suit::Hearts, false),
+      Slot::Card(Suit::Spades, false),
+      Slot::Card(Suit::Clubs, true),
+      Slot::Card(Suit::Diamonds, true),
+      Slot::Card(Suit::Hearts, true),
+      Slot::Card(Suit::Spades, true),
+      Slot::Joker { red: false },
+      Slot::Joker { red: true },
+    ]
+  );
+
+  let card = Card {
+    suit: Suit::Hearts,
+    rank: 12,
+    face_up: true,
+  };
+  assert_eq!(card.index(), 2 + 4 * 12 + 4 * 256);
+  assert_eq!(Card::from_finite(card.index() as u16), Some(card));
+  assert_eq!(Card::from_finite(Card::REPRS as u16), None);
+}
+
+/**
+ * Each value is at its index & encodes to it, which is how
+ * a table derived value should behave.
+ */
+fn assert_table_is_consistent<T>(all: &[T])
+where
+  T: FiniteEncoding + FiniteDecoding + PartialEq + std::fmt::Debug + Copy,
+{
+  assert_eq!(all.len(), T::REPRS);
+
+  for (index, value) in all.iter().enumerate() {
+    assert_eq!(value.into_finite::<u16>(), Some(index as u16));
+    assert_eq!(T::from_finite(index as u16), Some(*value));
+  }
+}
@@ -0,0 +1,102 @@
+use finite_repr::{Finite, FiniteDecoding, FiniteEncoding, FiniteRepr, FiniteTagged};
+
+fn main() {
+  #[derive(Clone, Copy, Finite)]
+  #[finite(tag = u8)]
+  union Payload {
+    #[finite(tag = 0)]
+    flag: bool,
+    #[finite(tag = 7)]
+    byte: u8,
+  }
+
+  #[derive(Clone, Copy, Finite)]
+  #[repr(C)]
+  struct Message {
+    kind: u8,
+    #[finite(tag = kind)]
+    payload: Payload,
+    urgent: bool,
+  }
+
+  // SAFETY: every `Message` below is built with `kind` matching
+  // the initialised arm of `payload`, or a tag of no arm at all.
+  unsafe impl FiniteTagged for Message {}
+
+  assert_eq!(Payload::REPRS, 258);
+  assert_eq!(Message::REPRS, 516);
+
+  let flag = |flag| Payload { flag };
+  let byte = |byte| Payload { byte };
+
+  for (kind, payload, urgent, number) in [
+    (0, flag(false), false, 0),
+    (0, flag(true), false, 1),
+    (7, byte(0), false, 2),
+    (7, byte(255), false, 257),
+    (0, flag(false), true, 258),
+    (7, byte(255), true, 515),
+  ] {
+    let message = Message {
+      kind,
+      payload,
+      urgent,
+    };
+    assert_eq!(message.into_finite::<u16>(), Some(number));
+
+    let decoded = Message::from_finite(number).unwrap();
+    assert_eq!(decoded.kind, kind);
+    assert_eq!(decoded.urgent, urgent);
+    unsafe {
+      match kind {
+        0 => assert_eq!(decoded.payload.flag, payload.flag),
+        _ => assert_eq!(decoded.payload.byte, payload.byte),
+      }
+    }
+  }
+
+  // A tag that doesn't identify any arm can't be encoded.
+  let unknown = Message {
+    kind: 3,
+    payload: byte(0),
+    urgent: false,
+  };
+  assert_eq!(unknown.into_finite::<u16>(), None);
+  assert!(Message::from_finite(516u16).is_none());
+
+  #[derive(Clone, Copy, Finite)]
+  struct Pair(#[finite(tag = 1)] Payload, u8);
+
+  // SAFETY: each `Pair` holds the tag of its initialised arm.
+  unsafe impl FiniteTagged for Pair {}
+
+  assert_eq!(Pair::REPRS, 258);
+  assert_eq!(Pair(byte(1), 7).into_finite::<u16>(), Some(3));
+  assert_eq!(Pair::from_finite(3u16).map(|p| p.1), Some(7));
+
+  #[derive(Clone, Copy, Finite)]
+  enum Event {
+    Empty,
+    Tagged {
+      #[finite(tag = kind)]
+      payload: Payload,
+      kind: u8,
+    },
+  }
+
+  // SAFETY: each tagged event holds the tag of its initialised arm.
+  unsafe impl FiniteTagged for Event {}
+
+  assert_eq!(Event::REPRS, 259);
+
+  let event = Event::Tagged {
+    payload: flag(true),
+    kind: 0,
+  };
+  assert_eq!(event.into_finite::<u16>(), Some(2));
+
+  match Event::from_finite(258u16) {
+    Some(Event::Tagged { payload, kind: 7 }) => assert_eq!(unsafe { payload.byte }, 255),
+    _ => panic!("expected the last tagged event"),
+  }
+}